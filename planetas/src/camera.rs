@@ -1,16 +1,36 @@
-use glam::{Mat4, Vec3};
+use glam::{Mat3, Mat4, Quat, Vec3};
 use std::f32::consts::PI;
 
+/// Distancia máxima de la cámara orbital a su cuerpo.
+const MAX_ORBIT_DISTANCE: f32 = 150.0;
+
+/// Posición y orientación de la cámara en un paso de la simulación, para
+/// interpolar entre pasos al dibujar.
+#[derive(Debug, Clone, Copy)]
+pub struct CameraPose {
+    pub position: Vec3,
+    pub orientation: Quat,
+}
+
+impl CameraPose {
+    pub fn lerp(self, other: Self, t: f32) -> Self {
+        Self {
+            position: self.position.lerp(other.position, t),
+            orientation: self.orientation.slerp(other.orientation, t),
+        }
+    }
+}
+
+/// Cámara de 6 grados de libertad. La orientación es un cuaternión que lleva los
+/// ejes locales (X derecha, Y arriba, -Z adelante) al mundo, así que no hay
+/// gimbal lock: se puede girar sobre cualquier eje, rizar el rizo y alabear.
 pub struct Camera {
     pub position: Vec3,
-    pub target: Vec3,
-    pub up: Vec3,
+    pub orientation: Quat,
     pub aspect: f32,
     pub fovy: f32,
     pub znear: f32,
     pub zfar: f32,
-    pub yaw: f32,
-    pub pitch: f32,
     pub speed: f32,
     pub sensitivity: f32,
 }
@@ -19,35 +39,33 @@ impl Camera {
     pub fn new(width: u32, height: u32) -> Self {
         Self {
             position: Vec3::new(0.0, 8.0, 35.0),
-            target: Vec3::ZERO,
-            up: Vec3::Y,
+            // Mirando hacia -Z, un poco hacia abajo
+            orientation: Quat::from_rotation_x(-0.2),
             aspect: width as f32 / height as f32,
             fovy: PI / 3.0,
             znear: 0.1,
             zfar: 200.0,
-            yaw: -PI / 2.0,
-            pitch: -0.2,
             speed: 15.0,
             sensitivity: 0.1,
         }
     }
 
     pub fn build_view_projection_matrix(&self) -> Mat4 {
-        let view = Mat4::look_at_rh(self.position, self.position + self.get_forward(), self.up);
+        let view = Mat4::look_to_rh(self.position, self.get_forward(), self.get_up());
         let proj = Mat4::perspective_rh(self.fovy, self.aspect, self.znear, self.zfar);
         proj * view
     }
 
     pub fn get_forward(&self) -> Vec3 {
-        Vec3::new(
-            self.yaw.cos() * self.pitch.cos(),
-            self.pitch.sin(),
-            self.yaw.sin() * self.pitch.cos(),
-        ).normalize()
+        self.orientation * Vec3::NEG_Z
     }
 
     pub fn get_right(&self) -> Vec3 {
-        self.get_forward().cross(self.up).normalize()
+        self.orientation * Vec3::X
+    }
+
+    pub fn get_up(&self) -> Vec3 {
+        self.orientation * Vec3::Y
     }
 
     pub fn move_forward(&mut self, dt: f32) {
@@ -66,23 +84,132 @@ impl Camera {
         self.position += self.get_right() * self.speed * dt;
     }
 
+    /// Sube según el eje Y de la cámara (no el del mundo), como en un simulador espacial.
     pub fn move_up(&mut self, dt: f32) {
-        self.position += self.up * self.speed * dt;
+        self.position += self.get_up() * self.speed * dt;
     }
 
     pub fn move_down(&mut self, dt: f32) {
-        self.position -= self.up * self.speed * dt;
+        self.position -= self.get_up() * self.speed * dt;
     }
 
+    /// Gira sobre los ejes locales de la cámara: yaw positivo a la derecha y
+    /// pitch positivo hacia arriba, sin límites.
     pub fn rotate(&mut self, delta_yaw: f32, delta_pitch: f32) {
-        self.yaw += delta_yaw * self.sensitivity;
-        self.pitch += delta_pitch * self.sensitivity;
-        
-        // Limitar el pitch para evitar gimbal lock
-        self.pitch = self.pitch.clamp(-PI / 2.0 + 0.1, PI / 2.0 - 0.1);
+        self.orientation = (self.orientation
+            * Quat::from_rotation_y(-delta_yaw * self.sensitivity)
+            * Quat::from_rotation_x(delta_pitch * self.sensitivity))
+            .normalize();
+    }
+
+    /// Alabeo sobre el eje de vista; positivo inclina la cámara a la derecha.
+    pub fn roll(&mut self, delta_roll: f32) {
+        self.orientation = (self.orientation * Quat::from_rotation_z(-delta_roll * self.sensitivity)).normalize();
+    }
+
+    /// Orienta la cámara en la dirección `dir` (no hace falta que sea unitaria),
+    /// con el horizonte nivelado respecto al eje Y del mundo.
+    pub fn look_towards(&mut self, dir: Vec3) {
+        self.orientation = look_rotation(dir, Vec3::Y);
+    }
+
+    /// Rotación con el ratón: `sensitivity` son los grados girados por píxel.
+    pub fn look(&mut self, delta_x: f32, delta_y: f32) {
+        self.rotate(delta_x.to_radians(), -delta_y.to_radians());
+    }
+
+    /// Multiplica la velocidad por 1.2 por cada paso de la rueda.
+    pub fn change_speed(&mut self, steps: f32) {
+        self.speed = (self.speed * 1.2f32.powf(steps)).clamp(1.0, 500.0);
+    }
+
+    /// Cierra (pasos positivos) o abre el campo de visión, 2° por paso.
+    pub fn zoom(&mut self, steps: f32) {
+        self.fovy = (self.fovy - (steps * 2.0).to_radians()).clamp(20f32.to_radians(), 100f32.to_radians());
+    }
+
+    pub fn pose(&self) -> CameraPose {
+        CameraPose {
+            position: self.position,
+            orientation: self.orientation,
+        }
+    }
+
+    pub fn set_pose(&mut self, pose: CameraPose) {
+        self.position = pose.position;
+        self.orientation = pose.orientation;
     }
 
     pub fn resize(&mut self, width: u32, height: u32) {
         self.aspect = width as f32 / height as f32;
     }
-}
\ No newline at end of file
+}
+
+/// Orientación de cámara que mira hacia `dir` con el eje Y local lo más cerca
+/// posible de `up`. Si `dir` es paralela a `up` se elige un eje cualquiera.
+pub fn look_rotation(dir: Vec3, up: Vec3) -> Quat {
+    let forward = dir.normalize();
+    let right = forward.cross(up).try_normalize().unwrap_or_else(|| forward.any_orthonormal_vector());
+    let up = right.cross(forward);
+    Quat::from_mat3(&Mat3::from_cols(right, up, -forward)).normalize()
+}
+
+/// Cámara orbital que sigue a un cuerpo: gira alrededor del punto de mira a una
+/// distancia dada. El punto de mira es la posición actual del cuerpo más `pan`.
+pub struct OrbitCamera {
+    pub body: usize,
+    pub distance: f32,
+    pub min_distance: f32,
+    pub azimuth: f32,
+    pub elevation: f32,
+    pub pan: Vec3,
+}
+
+impl OrbitCamera {
+    /// Empieza a orbitar `body` desde la posición actual de la cámara, sin saltos.
+    pub fn new(body: usize, body_position: Vec3, body_radius: f32, camera: &Camera) -> Self {
+        let offset = camera.position - body_position;
+        let min_distance = body_radius * 1.5;
+        let distance = offset.length().clamp(min_distance, MAX_ORBIT_DISTANCE);
+        let dir = offset.try_normalize().unwrap_or(Vec3::Z);
+
+        Self {
+            body,
+            distance,
+            min_distance,
+            azimuth: dir.z.atan2(dir.x),
+            elevation: dir.y.clamp(-1.0, 1.0).asin(),
+            pan: Vec3::ZERO,
+        }
+    }
+
+    /// Gira alrededor del punto de mira (radianes, escalados por `sensitivity`).
+    pub fn rotate(&mut self, delta_azimuth: f32, delta_elevation: f32, sensitivity: f32) {
+        self.azimuth += delta_azimuth * sensitivity;
+        self.elevation = (self.elevation + delta_elevation * sensitivity)
+            .clamp(-PI / 2.0 + 0.05, PI / 2.0 - 0.05);
+    }
+
+    /// Acerca (pasos positivos) o aleja la cámara un 10% por paso.
+    pub fn zoom(&mut self, steps: f32) {
+        self.distance = (self.distance * 0.9f32.powf(steps)).clamp(self.min_distance, MAX_ORBIT_DISTANCE);
+    }
+
+    /// Desplaza el punto de mira en el plano de la pantalla, proporcional a la distancia.
+    pub fn pan(&mut self, camera: &Camera, delta_right: f32, delta_up: f32) {
+        self.pan += (camera.get_right() * delta_right + camera.get_up() * delta_up) * self.distance;
+    }
+
+    /// Coloca la cámara para mirar a `body_position` desde la órbita actual.
+    pub fn apply(&self, camera: &mut Camera, body_position: Vec3) {
+        let focus = body_position + self.pan;
+        let offset = Vec3::new(
+            self.elevation.cos() * self.azimuth.cos(),
+            self.elevation.sin(),
+            self.elevation.cos() * self.azimuth.sin(),
+        ) * self.distance;
+
+        camera.position = focus + offset;
+        camera.look_towards(-offset);
+    }
+}
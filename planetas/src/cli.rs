@@ -0,0 +1,123 @@
+use std::path::PathBuf;
+
+use crate::renderer::{PostSettings, TimeSettings};
+
+const USAGE: &str = "Uso: planetas [--scene ARCHIVO.ron] [--headless] [--frames N] [--out DIR] [--width W] [--height H]\n       [--tonemap aces|reinhard|agx] [--exposure E] [--no-bloom] [--no-hud]\n       [--time T] [--time-scale S] [--shader-time-scale S] [--record ARCHIVO.ron | --replay ARCHIVO.ron]\n       [--camera-path ARCHIVO.ron] [--play-path] [--gravity] [--hot-reload]";
+
+/// Opciones de línea de comandos del binario `planetas`.
+pub struct Options {
+    pub scene: Option<PathBuf>,
+    pub headless: bool,
+    /// Frames en modo headless; por defecto los de la reproducción o 60
+    pub frames: Option<u32>,
+    pub out_dir: PathBuf,
+    pub width: u32,
+    pub height: u32,
+    pub post: PostSettings,
+    pub hud: bool,
+    pub time: TimeSettings,
+    pub record: Option<PathBuf>,
+    pub replay: Option<PathBuf>,
+    pub camera_path: Option<PathBuf>,
+    /// Empezar reproduciendo el camino de cámara
+    pub play_path: bool,
+    /// Empezar en el modo gravedad (N-cuerpos)
+    pub gravity: bool,
+    /// Leer los shaders de `src/shaders/` y recompilarlos al guardarlos
+    pub hot_reload: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            scene: None,
+            headless: false,
+            frames: None,
+            out_dir: PathBuf::from("frames"),
+            width: 1280,
+            height: 720,
+            post: PostSettings::default(),
+            hud: true,
+            time: TimeSettings::default(),
+            record: None,
+            replay: None,
+            camera_path: None,
+            play_path: false,
+            gravity: false,
+            hot_reload: false,
+        }
+    }
+}
+
+impl Options {
+    pub fn from_args() -> Result<Self, String> {
+        Self::parse(std::env::args().skip(1))
+    }
+
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut options = Self::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--scene" => options.scene = Some(PathBuf::from(require_value(&arg, args.next())?)),
+                "--headless" => options.headless = true,
+                "--frames" => options.frames = Some(parse_value(&arg, args.next())?),
+                "--out" => options.out_dir = PathBuf::from(require_value(&arg, args.next())?),
+                "--width" => options.width = parse_value(&arg, args.next())?,
+                "--height" => options.height = parse_value(&arg, args.next())?,
+                "--tonemap" => options.post.tonemapper = require_value(&arg, args.next())?.parse()?,
+                "--exposure" => options.post.exposure = parse_value(&arg, args.next())?,
+                "--no-bloom" => options.post.bloom = false,
+                "--no-hud" => options.hud = false,
+                "--time" => options.time.start = parse_value(&arg, args.next())?,
+                "--time-scale" => options.time.scale = parse_value(&arg, args.next())?,
+                "--shader-time-scale" => options.time.shader_scale = Some(parse_value(&arg, args.next())?),
+                "--record" => options.record = Some(PathBuf::from(require_value(&arg, args.next())?)),
+                "--replay" => options.replay = Some(PathBuf::from(require_value(&arg, args.next())?)),
+                "--camera-path" => options.camera_path = Some(PathBuf::from(require_value(&arg, args.next())?)),
+                "--play-path" => options.play_path = true,
+                "--gravity" => options.gravity = true,
+                "--hot-reload" => options.hot_reload = true,
+                "--help" | "-h" => return Err(USAGE.to_string()),
+                other => return Err(format!("argumento desconocido `{}`\n{}", other, USAGE)),
+            }
+        }
+
+        if options.width == 0 || options.height == 0 {
+            return Err("--width y --height deben ser mayores que 0".to_string());
+        }
+
+        if !(options.post.exposure.is_finite() && options.post.exposure > 0.0) {
+            return Err("--exposure debe ser mayor que 0".to_string());
+        }
+
+        if options.record.is_some() && (options.headless || options.replay.is_some()) {
+            return Err("--record solo se puede usar con ventana y sin --replay".to_string());
+        }
+
+        if options.hot_reload && options.headless {
+            return Err("--hot-reload solo se puede usar con ventana".to_string());
+        }
+
+        if options.play_path && options.camera_path.is_none() {
+            return Err("--play-path necesita --camera-path".to_string());
+        }
+
+        let times = [Some(options.time.start), Some(options.time.scale), options.time.shader_scale];
+        if !times.into_iter().flatten().all(f32::is_finite) {
+            return Err("--time, --time-scale y --shader-time-scale deben ser números finitos".to_string());
+        }
+
+        Ok(options)
+    }
+}
+
+fn require_value(flag: &str, value: Option<String>) -> Result<String, String> {
+    value.ok_or_else(|| format!("falta el valor para `{}`\n{}", flag, USAGE))
+}
+
+fn parse_value<T: std::str::FromStr>(flag: &str, value: Option<String>) -> Result<T, String> {
+    let value = require_value(flag, value)?;
+    value.parse().map_err(|_| format!("valor inválido para `{}`: `{}`", flag, value))
+}
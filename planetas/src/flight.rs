@@ -0,0 +1,130 @@
+use glam::{Quat, Vec3};
+
+use crate::camera::{Camera, CameraPose};
+
+/// Aceleración del motor en unidades/s² con el mando a fondo.
+const THRUST: f32 = 20.0;
+/// Multiplicador del empuje con el turbo.
+const BOOST_MULTIPLIER: f32 = 3.0;
+/// Resistencia lineal en 1/s: sin empuje la nave se frena sola, y la velocidad
+/// máxima es `THRUST / DRAG` (el triple con turbo).
+const DRAG: f32 = 1.0;
+/// Giro con teclado en rad/s.
+const TURN_RATE: f32 = 1.2;
+
+/// Posición de la cámara en tercera persona, en ejes de la nave (detrás y arriba).
+pub const CHASE_OFFSET: Vec3 = Vec3::new(0.0, 1.5, 6.0);
+/// Posición de la cámara en la cabina, en ejes de la nave.
+const COCKPIT_OFFSET: Vec3 = Vec3::new(0.0, 0.35, 0.0);
+/// Rapidez con la que la cámara en tercera persona alcanza su sitio (1/s).
+const FOLLOW_RATE: f32 = 6.0;
+/// Estado del casco con la nave intacta (en %).
+pub const MAX_HULL: f32 = 100.0;
+
+/// Desde dónde mira la cámara cuando se pilota la nave.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ShipView {
+    /// Detrás de la nave, siguiéndola con algo de retraso
+    Chase,
+    /// Dentro de la nave, rígida con ella
+    Cockpit,
+}
+
+/// Mandos de un paso: empuje por eje local en [-1, 1] (X derecha, Y arriba,
+/// -Z adelante) y giros en [-1, 1] (yaw a la derecha, pitch arriba, roll a la derecha).
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ShipControls {
+    pub thrust: Vec3,
+    pub yaw: f32,
+    pub pitch: f32,
+    pub roll: f32,
+    pub boost: bool,
+}
+
+/// Nave pilotable con inercia: el empuje acelera, la resistencia frena y la
+/// orientación se controla directamente.
+pub struct Ship {
+    pub position: Vec3,
+    pub orientation: Quat,
+    pub velocity: Vec3,
+    /// Estado del casco en %, que bajan los choques fuertes
+    pub hull: f32,
+}
+
+impl Ship {
+    pub fn new(position: Vec3, orientation: Quat) -> Self {
+        Self {
+            position,
+            orientation,
+            velocity: Vec3::ZERO,
+            hull: MAX_HULL,
+        }
+    }
+
+    pub fn pose(&self) -> CameraPose {
+        CameraPose {
+            position: self.position,
+            orientation: self.orientation,
+        }
+    }
+
+    /// Coloca la nave en `pose` y la detiene (por ejemplo al llegar de un warp).
+    pub fn set_pose(&mut self, pose: CameraPose) {
+        self.position = pose.position;
+        self.orientation = pose.orientation;
+        self.velocity = Vec3::ZERO;
+    }
+
+    pub fn speed(&self) -> f32 {
+        self.velocity.length()
+    }
+
+    /// Integra un paso: giros, empuje, gravedad (`gravity`, ya en unidades/s²),
+    /// resistencia y posición.
+    pub fn update(&mut self, controls: &ShipControls, gravity: Vec3, dt: f32) {
+        let turn = TURN_RATE * dt;
+        self.orientation = (self.orientation
+            * Quat::from_rotation_y(-controls.yaw * turn)
+            * Quat::from_rotation_x(controls.pitch * turn)
+            * Quat::from_rotation_z(-controls.roll * turn))
+            .normalize();
+
+        let boost = if controls.boost { BOOST_MULTIPLIER } else { 1.0 };
+        let thrust = controls.thrust.clamp_length_max(1.0) * THRUST * boost;
+        self.velocity += (self.orientation * thrust + gravity) * dt;
+        self.velocity *= (-DRAG * dt).exp();
+        self.position += self.velocity * dt;
+    }
+
+    /// Giro con el ratón (píxeles), igual que `Camera::look`: `sensitivity` son
+    /// los grados girados por píxel.
+    pub fn look(&mut self, delta_x: f32, delta_y: f32, sensitivity: f32) {
+        self.orientation = (self.orientation
+            * Quat::from_rotation_y(-(delta_x * sensitivity).to_radians())
+            * Quat::from_rotation_x(-(delta_y * sensitivity).to_radians()))
+            .normalize();
+    }
+
+    /// Resta `amount` puntos al casco. Devuelve true si lo deja a 0.
+    pub fn damage(&mut self, amount: f32) -> bool {
+        self.hull = (self.hull - amount).max(0.0);
+        self.hull == 0.0
+    }
+
+    /// Coloca la cámara según la vista: en tercera persona se acerca a su sitio
+    /// con un suavizado exponencial; en la cabina va pegada a la nave.
+    pub fn follow(&self, camera: &mut Camera, view: ShipView, dt: f32) {
+        match view {
+            ShipView::Chase => {
+                let target = self.position + self.orientation * CHASE_OFFSET;
+                let t = 1.0 - (-FOLLOW_RATE * dt).exp();
+                camera.position = camera.position.lerp(target, t);
+                camera.orientation = camera.orientation.slerp(self.orientation, t);
+            }
+            ShipView::Cockpit => {
+                camera.position = self.position + self.orientation * COCKPIT_OFFSET;
+                camera.orientation = self.orientation;
+            }
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+use crate::cli::Options;
+use crate::renderer::{CameraPath, Recording, Renderer, Scene, FIXED_STEP};
+
+/// Frames por defecto si no se indica `--frames` ni hay reproducción.
+const DEFAULT_FRAMES: u32 = 60;
+
+/// Renderiza `options.frames` frames sin ventana y los guarda como PNG en `options.out_dir`.
+///
+/// Con una grabación (`--replay`) se usa su tamaño de ventana, para que los clics
+/// caigan en el mismo sitio, y por defecto se renderizan todos sus pasos. Con
+/// `--play-path` se renderiza por defecto una pasada del camino de cámara.
+pub fn run(
+    options: &Options,
+    scene: Scene,
+    replay: Option<Recording>,
+    camera_path: Option<CameraPath>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    std::fs::create_dir_all(&options.out_dir)?;
+
+    let (width, height) = replay.as_ref().map_or((options.width, options.height), |r| (r.width, r.height));
+    // Sin --frames: toda la reproducción, o una pasada del camino de cámara
+    let path_frames = camera_path.as_ref().filter(|_| options.play_path).map(|camera_path| {
+        let length = camera_path.duration() - camera_path.keyframes[0].time;
+        (length / FIXED_STEP.as_secs_f32()).ceil() as u32 + 1
+    });
+    let frames = options.frames
+        .or(replay.as_ref().map(|r| r.steps as u32))
+        .or(path_frames)
+        .unwrap_or(DEFAULT_FRAMES);
+
+    let mut renderer = pollster::block_on(Renderer::new_headless(width, height, scene, options.post));
+    renderer.set_hud_visible(options.hud);
+    renderer.set_time_settings(options.time);
+    renderer.set_gravity(options.gravity);
+    if let Some(recording) = replay {
+        renderer.start_replay(recording);
+    }
+    if let Some(camera_path) = camera_path {
+        renderer.set_camera_path(camera_path)?;
+        if options.play_path {
+            renderer.toggle_camera_path();
+        }
+    }
+
+    // Un paso fijo de simulación por frame: la salida no depende de lo que tarde cada frame
+    for frame in 0..frames {
+        renderer.update(FIXED_STEP);
+        renderer.render()?;
+
+        let pixels = renderer.capture_frame().ok_or("no se pudo leer el frame offscreen")?;
+        let path = options.out_dir.join(format!("frame_{:05}.png", frame));
+        write_png(&path, width, height, &pixels)?;
+    }
+
+    println!("[headless] {} frames escritos en {}", frames, options.out_dir.display());
+    Ok(())
+}
+
+fn write_png(path: &Path, width: u32, height: u32, pixels: &[u8]) -> Result<(), Box<dyn std::error::Error>> {
+    let file = File::create(path)?;
+    let mut encoder = png::Encoder::new(BufWriter::new(file), width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(pixels)?;
+    Ok(())
+}
@@ -6,23 +6,61 @@ use winit::{
 
 mod renderer;
 mod camera;
+mod flight;
+mod cli;
+mod headless;
 
-use renderer::Renderer;
+use std::path::PathBuf;
+
+use renderer::{CameraPath, InputEvent, PostSettings, Recording, Renderer, Scene, TimeSettings};
+
+/// Shaders fuente del crate; `--hot-reload` los lee de aquí en vez de usar las
+/// copias embebidas en el binario.
+const SHADER_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/src/shaders");
 
 struct App {
     renderer: Option<Renderer>,
+    scene: Option<Scene>,
+    post: PostSettings,
+    hud: bool,
+    time: TimeSettings,
+    record: Option<PathBuf>,
+    replay: Option<Recording>,
+    camera_path: Option<CameraPath>,
+    play_path: bool,
+    gravity: bool,
+    /// Directorio de shaders a vigilar con `--hot-reload`
+    shader_dir: Option<PathBuf>,
     last_render_time: std::time::Instant,
 }
 
 impl ApplicationHandler for App {
     fn resumed(&mut self, event_loop: &winit::event_loop::ActiveEventLoop) {
-        if self.renderer.is_none() {
+        if let Some(scene) = self.scene.take() {
             let window_attributes = winit::window::Window::default_attributes()
                 .with_title("Planetas Celestes")
                 .with_inner_size(winit::dpi::LogicalSize::new(1280, 720));
             
             let window = event_loop.create_window(window_attributes).unwrap();
-            let renderer = pollster::block_on(Renderer::new(window));
+            let mut renderer = pollster::block_on(Renderer::new(window, scene, self.post));
+            renderer.set_hud_visible(self.hud);
+            renderer.set_time_settings(self.time);
+            renderer.set_gravity(self.gravity);
+            if let Some(dir) = self.shader_dir.take() {
+                renderer.watch_shaders(dir);
+            }
+            if let Some(path) = self.record.take() {
+                renderer.start_recording(path);
+            }
+            if let Some(recording) = self.replay.take() {
+                renderer.start_replay(recording);
+            }
+            if let Some(camera_path) = self.camera_path.take() {
+                renderer.set_camera_path(camera_path).expect("el camino ya se validó contra la escena");
+                if self.play_path {
+                    renderer.toggle_camera_path();
+                }
+            }
             self.renderer = Some(renderer);
         }
     }
@@ -38,7 +76,12 @@ impl ApplicationHandler for App {
         };
 
         match event {
-            WindowEvent::CloseRequested => event_loop.exit(),
+            WindowEvent::CloseRequested => {
+                if let Err(err) = renderer.stop_recording() {
+                    eprintln!("[grabacion] error: {}", err);
+                }
+                event_loop.exit();
+            }
             WindowEvent::Resized(physical_size) => {
                 renderer.resize(physical_size);
             }
@@ -50,7 +93,23 @@ impl ApplicationHandler for App {
                 },
                 ..
             } => {
-                renderer.input(key, state == ElementState::Pressed);
+                renderer.handle_input(InputEvent::Key { key, pressed: state == ElementState::Pressed });
+            }
+            WindowEvent::CursorMoved { position, .. } => {
+                renderer.handle_input(InputEvent::CursorMoved { x: position.x as f32, y: position.y as f32 });
+            }
+            WindowEvent::MouseInput { state, button, .. } => {
+                renderer.handle_input(InputEvent::MouseButton { button, pressed: state == ElementState::Pressed });
+            }
+            WindowEvent::MouseWheel { delta, .. } => {
+                let steps = match delta {
+                    MouseScrollDelta::LineDelta(_, y) => y,
+                    MouseScrollDelta::PixelDelta(position) => position.y as f32 / 50.0,
+                };
+                renderer.handle_input(InputEvent::Scroll { steps });
+            }
+            WindowEvent::Focused(false) => {
+                renderer.handle_input(InputEvent::MouseLook { enabled: false });
             }
             WindowEvent::RedrawRequested => {
                 let now = std::time::Instant::now();
@@ -67,25 +126,97 @@ impl ApplicationHandler for App {
                     Err(wgpu::SurfaceError::Timeout) => eprintln!("Surface timeout"),
                 }
                 
-                renderer.window.request_redraw();
+                renderer.request_redraw();
             }
             _ => {}
         }
     }
 
+    fn device_event(
+        &mut self,
+        _event_loop: &winit::event_loop::ActiveEventLoop,
+        _device_id: DeviceId,
+        event: DeviceEvent,
+    ) {
+        if let (Some(renderer), DeviceEvent::MouseMotion { delta }) = (&mut self.renderer, event) {
+            renderer.handle_input(InputEvent::MouseMotion { dx: delta.0 as f32, dy: delta.1 as f32 });
+        }
+    }
+
     fn about_to_wait(&mut self, _event_loop: &winit::event_loop::ActiveEventLoop) {
         if let Some(renderer) = &self.renderer {
-            renderer.window.request_redraw();
+            renderer.request_redraw();
         }
     }
 }
 
 fn main() {
     env_logger::init();
+
+    let options = match cli::Options::from_args() {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}", message);
+            std::process::exit(2);
+        }
+    };
+
+    let scene = match &options.scene {
+        Some(path) => Scene::load(path),
+        None => Ok(Scene::default_scene()),
+    };
+    let scene = match scene {
+        Ok(scene) => scene,
+        Err(err) => {
+            eprintln!("[escena] error: {}", err);
+            std::process::exit(2);
+        }
+    };
+
+    let replay = match options.replay.as_deref().map(Recording::load).transpose() {
+        Ok(replay) => replay,
+        Err(err) => {
+            eprintln!("[replay] error: {}", err);
+            std::process::exit(2);
+        }
+    };
+
+    // Se resuelve aquí contra la escena para fallar antes de abrir la ventana
+    let camera_path = options.camera_path.as_deref().map(|path| {
+        let mut camera_path = CameraPath::load(path)?;
+        camera_path.resolve_bodies(&scene.bodies)?;
+        Ok::<_, renderer::CameraPathError>(camera_path)
+    });
+    let camera_path = match camera_path.transpose() {
+        Ok(camera_path) => camera_path,
+        Err(err) => {
+            eprintln!("[camino] error: {}", err);
+            std::process::exit(2);
+        }
+    };
+
+    if options.headless {
+        if let Err(err) = headless::run(&options, scene, replay, camera_path) {
+            eprintln!("[headless] error: {}", err);
+            std::process::exit(1);
+        }
+        return;
+    }
+
     let event_loop = EventLoop::new().unwrap();
     
     let mut app = App {
         renderer: None,
+        scene: Some(scene),
+        post: options.post,
+        hud: options.hud,
+        time: options.time,
+        record: options.record,
+        replay,
+        camera_path,
+        play_path: options.play_path,
+        gravity: options.gravity,
+        shader_dir: options.hot_reload.then(|| PathBuf::from(SHADER_DIR)),
         last_render_time: std::time::Instant::now(),
     };
     
@@ -0,0 +1,342 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use glam::{EulerRot, Quat, Vec2, Vec3};
+use serde::Deserialize;
+
+use super::planets::Planet;
+
+/// Curva que une las posiciones de las claves.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+pub enum Spline {
+    /// Pasa por todas las claves con tangentes automáticas (Catmull-Rom uniforme)
+    #[default]
+    CatmullRom,
+    /// Bezier cúbica por tramos: cada clave puede fijar su tangente con `handle`;
+    /// sin `handle` se usa la misma tangente que Catmull-Rom
+    Bezier,
+}
+
+/// Curva de velocidad dentro de un tramo.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+pub enum Easing {
+    #[default]
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+}
+
+impl Easing {
+    /// Aplica la curva (cúbica) a `t` en [0, 1].
+    pub fn apply(self, t: f32) -> f32 {
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t * t,
+            Easing::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Easing::EaseInOut if t < 0.5 => 4.0 * t * t * t,
+            Easing::EaseInOut => 1.0 - (-2.0 * t + 2.0).powi(3) / 2.0,
+        }
+    }
+}
+
+/// Clave del camino. Como en los puntos de warp, si tiene `body` la posición,
+/// `look_at` y `handle` se miden desde el centro de ese cuerpo y lo siguen.
+///
+/// La orientación sale de `look_at` (un punto) o de `orientation`; sin ninguna de
+/// las dos la cámara mira en la dirección del camino.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Keyframe {
+    /// Segundos desde el inicio del camino
+    pub time: f32,
+    #[serde(default)]
+    pub body: Option<String>,
+    pub position: Vec3,
+    #[serde(default)]
+    pub look_at: Option<Vec3>,
+    /// Yaw y pitch en grados, con el convenio de la cámara: (0, 0) mira hacia -Z
+    /// como la vista inicial, yaw positivo gira a la derecha y pitch positivo sube
+    #[serde(default)]
+    pub orientation: Option<Vec2>,
+    /// Tangente de salida para `Spline::Bezier` (la de entrada es la opuesta)
+    #[serde(default)]
+    pub handle: Option<Vec3>,
+    /// Curva de velocidad del tramo que empieza en esta clave
+    #[serde(default)]
+    pub easing: Easing,
+    #[serde(skip)]
+    body_index: Option<usize>,
+}
+
+impl Keyframe {
+    fn world_point(&self, planets: &[Planet], local: Vec3) -> Vec3 {
+        match self.body_index {
+            Some(index) => planets[index].get_frame_matrix().transform_point3(local),
+            None => local,
+        }
+    }
+
+    fn world_position(&self, planets: &[Planet]) -> Vec3 {
+        self.world_point(planets, self.position)
+    }
+
+    fn world_look_at(&self, planets: &[Planet]) -> Option<Vec3> {
+        self.look_at.map(|target| self.world_point(planets, target))
+    }
+
+    /// Dirección de vista fijada por la clave, si la hay.
+    fn direction(&self, planets: &[Planet]) -> Option<Vec3> {
+        if let Some(target) = self.world_look_at(planets) {
+            return (target - self.world_position(planets)).try_normalize();
+        }
+        self.orientation.map(|angles| {
+            let (yaw, pitch) = (angles.x.to_radians(), angles.y.to_radians());
+            Quat::from_euler(EulerRot::YXZ, -yaw, pitch, 0.0) * Vec3::NEG_Z
+        })
+    }
+}
+
+/// Recorrido de cámara por claves, cargado desde un archivo RON (ver `paths/`).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CameraPath {
+    pub name: String,
+    #[serde(default)]
+    pub spline: Spline,
+    /// Al terminar vuelve a empezar. Si la última clave coincide con la primera,
+    /// el camino se trata como cerrado y el paso por ella es suave.
+    #[serde(default)]
+    pub looped: bool,
+    pub keyframes: Vec<Keyframe>,
+}
+
+#[derive(Debug)]
+pub enum CameraPathError {
+    Io { path: PathBuf, source: std::io::Error },
+    Parse { path: PathBuf, source: ron::error::SpannedError },
+    Invalid { field: String, message: String },
+}
+
+impl fmt::Display for CameraPathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CameraPathError::Io { path, source } => {
+                write!(f, "no se pudo leer el camino {}: {}", path.display(), source)
+            }
+            CameraPathError::Parse { path, source } => write!(f, "{}:{}", path.display(), source),
+            CameraPathError::Invalid { field, message } => write!(f, "{}: {}", field, message),
+        }
+    }
+}
+
+impl std::error::Error for CameraPathError {}
+
+impl CameraPath {
+    pub fn load(path: &Path) -> Result<Self, CameraPathError> {
+        let source = std::fs::read_to_string(path).map_err(|source| CameraPathError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let options = ron::Options::default()
+            .with_default_extension(ron::extensions::Extensions::IMPLICIT_SOME);
+        let camera_path: CameraPath = options.from_str(&source).map_err(|source| CameraPathError::Parse {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        camera_path.validate()?;
+        Ok(camera_path)
+    }
+
+    fn validate(&self) -> Result<(), CameraPathError> {
+        check(self.keyframes.len() >= 2, "keyframes", "hacen falta al menos 2 claves")?;
+
+        for (i, key) in self.keyframes.iter().enumerate() {
+            let field = |name: &str| format!("keyframes[{}].{}", i, name);
+            check(key.time.is_finite() && key.time >= 0.0, &field("time"), "debe ser mayor o igual que 0")?;
+            check(key.position.is_finite(), &field("position"), "debe ser finita")?;
+            check(
+                !(key.look_at.is_some() && key.orientation.is_some()),
+                &field("look_at"),
+                "no se puede usar junto con `orientation`",
+            )?;
+            if i > 0 {
+                check(
+                    key.time > self.keyframes[i - 1].time,
+                    &field("time"),
+                    "los tiempos deben ser estrictamente crecientes",
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Asigna a cada clave el índice de su `body` en la lista aplanada de cuerpos.
+    pub fn resolve_bodies(&mut self, planets: &[Planet]) -> Result<(), CameraPathError> {
+        for (i, key) in self.keyframes.iter_mut().enumerate() {
+            key.body_index = match &key.body {
+                Some(name) => Some(planets.iter().position(|planet| &planet.name == name).ok_or_else(|| {
+                    CameraPathError::Invalid {
+                        field: format!("keyframes[{}].body", i),
+                        message: format!("no existe un cuerpo llamado `{}`", name),
+                    }
+                })?),
+                None => None,
+            };
+        }
+        Ok(())
+    }
+
+    /// Tiempo de la última clave.
+    pub fn duration(&self) -> f32 {
+        self.keyframes.last().map_or(0.0, |key| key.time)
+    }
+
+    /// Posición y dirección de vista en el instante `time` desde el inicio del
+    /// camino (se repite si `looped`, si no se queda en los extremos).
+    pub fn sample(&self, time: f32, planets: &[Planet]) -> (Vec3, Vec3) {
+        let keys = &self.keyframes;
+        let start = keys[0].time;
+        let time = if self.looped {
+            start + (time - start).rem_euclid(self.duration() - start)
+        } else {
+            time.clamp(start, self.duration())
+        };
+
+        let segment = keys.windows(2).position(|pair| time < pair[1].time).unwrap_or(keys.len() - 2);
+        let (a, b) = (&keys[segment], &keys[segment + 1]);
+        let u = a.easing.apply(((time - a.time) / (b.time - a.time)).clamp(0.0, 1.0));
+
+        let position = self.position_at(segment, u, planets);
+
+        // Con dos puntos de mira se interpola el punto, para seguirlo durante el tramo;
+        // en otro caso se interpolan las direcciones (o la tangente si no hay ninguna)
+        let direction = match (a.world_look_at(planets), b.world_look_at(planets)) {
+            (Some(from), Some(to)) => from.lerp(to, u) - position,
+            _ => match (a.direction(planets), b.direction(planets)) {
+                (None, None) => self.tangent_at(segment, u, planets),
+                (from, to) => {
+                    // `from_rotation_arc` necesita vectores unitarios; si la tangente es
+                    // nula (claves en el mismo punto) se usa la dirección de la otra clave
+                    let from = from.or_else(|| self.tangent_at(segment, 0.0, planets).try_normalize());
+                    let to = to.or_else(|| self.tangent_at(segment, 1.0, planets).try_normalize());
+                    match (from.or(to), to.or(from)) {
+                        (Some(from), Some(to)) => Quat::IDENTITY.slerp(Quat::from_rotation_arc(from, to), u) * from,
+                        _ => self.tangent_at(segment, u, planets),
+                    }
+                }
+            },
+        };
+
+        (position, direction.try_normalize().unwrap_or(Vec3::NEG_Z))
+    }
+
+    /// Punto del tramo `segment` (de la clave `segment` a la siguiente) en `u` ∈ [0, 1].
+    fn position_at(&self, segment: usize, u: f32, planets: &[Planet]) -> Vec3 {
+        let [p0, p1, p2, p3] = self.control_points(segment, planets);
+
+        match self.spline {
+            Spline::CatmullRom => {
+                0.5 * (2.0 * p1
+                    + (p2 - p0) * u
+                    + (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3) * u * u
+                    + (3.0 * p1 - p0 - 3.0 * p2 + p3) * u * u * u)
+            }
+            Spline::Bezier => {
+                let keys = &self.keyframes;
+                let handle_out = keys[segment].handle.unwrap_or((p2 - p0) / 6.0);
+                let handle_in = keys[segment + 1].handle.unwrap_or((p3 - p1) / 6.0);
+                let (c1, c2) = (p1 + handle_out, p2 - handle_in);
+
+                let v = 1.0 - u;
+                p1 * (v * v * v) + c1 * (3.0 * v * v * u) + c2 * (3.0 * v * u * u) + p2 * (u * u * u)
+            }
+        }
+    }
+
+    fn tangent_at(&self, segment: usize, u: f32, planets: &[Planet]) -> Vec3 {
+        const DELTA: f32 = 0.01;
+        let (u0, u1) = ((u - DELTA).max(0.0), (u + DELTA).min(1.0));
+        self.position_at(segment, u1, planets) - self.position_at(segment, u0, planets)
+    }
+
+    /// Posiciones de las claves anterior, inicial, final y siguiente del tramo.
+    /// En los extremos se repite la clave, salvo en un camino cerrado, donde se
+    /// toman las del otro extremo.
+    fn control_points(&self, segment: usize, planets: &[Planet]) -> [Vec3; 4] {
+        let keys = &self.keyframes;
+        let last = keys.len() - 1;
+        let closed = self.looped && keys[0].body == keys[last].body && keys[0].position == keys[last].position;
+
+        let before = match segment {
+            0 if closed => last - 1,
+            0 => 0,
+            _ => segment - 1,
+        };
+        let after = match segment + 1 {
+            end if end == last && closed => 1,
+            end if end == last => last,
+            end => end + 1,
+        };
+
+        [before, segment, segment + 1, after].map(|i| keys[i].world_position(planets))
+    }
+}
+
+fn check(condition: bool, field: &str, message: &str) -> Result<(), CameraPathError> {
+    if condition {
+        Ok(())
+    } else {
+        Err(CameraPathError::Invalid {
+            field: field.to_string(),
+            message: message.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path(source: &str) -> CameraPath {
+        let options = ron::Options::default()
+            .with_default_extension(ron::extensions::Extensions::IMPLICIT_SOME);
+        let camera_path: CameraPath = options.from_str(source).unwrap();
+        camera_path.validate().unwrap();
+        camera_path
+    }
+
+    #[test]
+    fn orientation_zero_looks_like_the_default_camera() {
+        let key = &path("(name: \"t\", keyframes: [
+            (time: 0.0, position: (0.0, 0.0, 0.0), orientation: (0.0, 0.0)),
+            (time: 1.0, position: (0.0, 0.0, 1.0), orientation: (90.0, 0.0)),
+        ])").keyframes;
+        assert!(key[0].direction(&[]).unwrap().abs_diff_eq(Vec3::NEG_Z, 1e-5));
+        assert!(key[1].direction(&[]).unwrap().abs_diff_eq(Vec3::X, 1e-5));
+    }
+
+    #[test]
+    fn orientation_blends_into_the_path_tangent() {
+        let camera_path = path("(name: \"t\", keyframes: [
+            (time: 0.0, position: (0.0, 0.0, 0.0), orientation: (0.0, 0.0)),
+            (time: 1.0, position: (10.0, 0.0, 0.0)),
+        ])");
+        let (_, direction) = camera_path.sample(0.5, &[]);
+        let expected = Vec3::new(1.0, 0.0, -1.0).normalize();
+        assert!(direction.abs_diff_eq(expected, 1e-3), "{:?}", direction);
+    }
+
+    #[test]
+    fn zero_tangent_keeps_the_key_direction() {
+        let camera_path = path("(name: \"t\", keyframes: [
+            (time: 0.0, position: (0.0, 0.0, 0.0), orientation: (90.0, 0.0)),
+            (time: 1.0, position: (0.0, 0.0, 0.0)),
+        ])");
+        for time in [0.0, 0.5, 1.0] {
+            let (_, direction) = camera_path.sample(time, &[]);
+            assert!(direction.abs_diff_eq(Vec3::X, 1e-5), "{:?}", direction);
+        }
+    }
+}
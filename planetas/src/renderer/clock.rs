@@ -0,0 +1,241 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// Escala máxima (en valor absoluto) que admiten los controles de tiempo.
+pub const MAX_TIME_SCALE: f32 = 1024.0;
+
+/// Paso fijo de la simulación (60 Hz). `Renderer::update` acumula el tiempo real
+/// y avanza la simulación en pasos de este tamaño, así que la misma secuencia de
+/// entradas produce siempre el mismo recorrido.
+pub const FIXED_STEP: Duration = Duration::from_nanos(16_666_667);
+/// Máximo de pasos por frame; si el frame tarda más se descarta el resto en
+/// lugar de intentar alcanzarlo (y tardar aún más en el siguiente).
+const MAX_STEPS_PER_FRAME: u32 = 8;
+
+/// Tiempo real aún no simulado (menor que `FIXED_STEP` tras cada `steps`).
+pub struct FixedTimestep {
+    accumulator: Duration,
+}
+
+impl FixedTimestep {
+    pub fn new() -> Self {
+        Self { accumulator: Duration::ZERO }
+    }
+
+    /// Suma el tiempo real de un frame y devuelve cuántos pasos fijos simular.
+    pub fn steps(&mut self, frame_dt: Duration) -> u32 {
+        self.accumulator += frame_dt;
+        let mut steps = 0;
+        while self.accumulator >= FIXED_STEP {
+            if steps == MAX_STEPS_PER_FRAME {
+                self.accumulator = Duration::ZERO;
+                break;
+            }
+            self.accumulator -= FIXED_STEP;
+            steps += 1;
+        }
+        steps
+    }
+
+    /// Fracción del paso siguiente que ya pasó, para interpolar al dibujar.
+    pub fn alpha(&self) -> f32 {
+        self.accumulator.as_secs_f32() / FIXED_STEP.as_secs_f32()
+    }
+}
+
+/// Reloj de simulación: avanza `dt * scale` por paso, puede ir hacia atrás
+/// (escala negativa), pausarse y avanzar de paso en paso estando en pausa.
+/// Como las órbitas son función pura del tiempo, saltar a cualquier instante
+/// con `set_time` es seguro.
+pub struct SimClock {
+    pub time: f32,
+    pub scale: f32,
+    pub paused: bool,
+    /// Valor de `time` antes del último `advance`, para interpolar al dibujar
+    previous_time: f32,
+    pending_steps: u32,
+}
+
+impl SimClock {
+    pub fn new(time: f32, scale: f32) -> Self {
+        Self {
+            time,
+            scale,
+            paused: false,
+            previous_time: time,
+            pending_steps: 0,
+        }
+    }
+
+    /// Avanza el reloj. En pausa solo se mueve si hay pasos pendientes de `step`.
+    pub fn advance(&mut self, dt: f32) {
+        self.previous_time = self.time;
+        if !self.paused {
+            self.time += dt * self.scale;
+        } else if self.pending_steps > 0 {
+            self.pending_steps -= 1;
+            self.time += dt * self.scale;
+        }
+    }
+
+    /// Salta a `time` sin interpolar desde el valor anterior.
+    pub fn set_time(&mut self, time: f32) {
+        self.time = time;
+        self.previous_time = time;
+    }
+
+    /// Tiempo entre el paso anterior (`alpha` = 0) y el actual (`alpha` = 1).
+    pub fn interpolated(&self, alpha: f32) -> f32 {
+        self.previous_time + (self.time - self.previous_time) * alpha
+    }
+
+    /// Pide avanzar un único paso (solo tiene efecto en pausa).
+    pub fn step(&mut self) {
+        if self.paused {
+            self.pending_steps += 1;
+        }
+    }
+
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+        self.pending_steps = 0;
+    }
+
+    pub fn set_scale(&mut self, scale: f32) {
+        self.scale = scale.clamp(-MAX_TIME_SCALE, MAX_TIME_SCALE);
+    }
+}
+
+/// Estado inicial de los relojes (por ejemplo desde la línea de comandos).
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct TimeSettings {
+    /// Instante de simulación inicial, en segundos
+    pub start: f32,
+    /// Escala del tiempo orbital (negativa para ir hacia atrás)
+    pub scale: f32,
+    /// Escala propia de la animación de los shaders; None la enlaza al tiempo orbital
+    pub shader_scale: Option<f32>,
+}
+
+impl Default for TimeSettings {
+    fn default() -> Self {
+        Self {
+            start: 0.0,
+            scale: 1.0,
+            shader_scale: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DT: f32 = 0.5;
+
+    fn close(a: f32, b: f32) -> bool {
+        (a - b).abs() < 1e-5
+    }
+
+    #[test]
+    fn clock_advances_with_scale() {
+        let mut clock = SimClock::new(10.0, 2.0);
+        clock.advance(DT);
+        assert!(close(clock.time, 11.0));
+        clock.set_scale(-4.0);
+        clock.advance(DT);
+        assert!(close(clock.time, 9.0));
+        clock.set_scale(1e6);
+        assert_eq!(clock.scale, MAX_TIME_SCALE);
+    }
+
+    #[test]
+    fn paused_clock_only_moves_on_step() {
+        let mut clock = SimClock::new(0.0, 1.0);
+        clock.set_paused(true);
+        clock.advance(DT);
+        assert_eq!(clock.time, 0.0);
+
+        clock.step();
+        clock.step();
+        for _ in 0..4 {
+            clock.advance(DT);
+        }
+        assert!(close(clock.time, 2.0 * DT));
+
+        // Reanudar descarta los pasos pedidos que no se usaron
+        clock.step();
+        clock.set_paused(false);
+        clock.set_paused(true);
+        clock.advance(DT);
+        assert!(close(clock.time, 2.0 * DT));
+
+        // Fuera de pausa `step` no acumula nada
+        clock.set_paused(false);
+        clock.step();
+        clock.set_paused(true);
+        clock.advance(DT);
+        assert!(close(clock.time, 2.0 * DT));
+    }
+
+    #[test]
+    fn interpolates_between_steps() {
+        let mut clock = SimClock::new(1.0, 1.0);
+        clock.advance(DT);
+        assert!(close(clock.interpolated(0.0), 1.0));
+        assert!(close(clock.interpolated(0.5), 1.0 + DT / 2.0));
+        assert!(close(clock.interpolated(1.0), 1.0 + DT));
+
+        clock.set_time(5.0);
+        assert!(close(clock.interpolated(0.0), 5.0));
+    }
+
+    #[test]
+    fn fixed_timestep_carries_the_remainder() {
+        let mut timestep = FixedTimestep::new();
+        assert_eq!(timestep.steps(FIXED_STEP / 2), 0);
+        assert!((timestep.alpha() - 0.5).abs() < 1e-3);
+        // FIXED_STEP tiene un número impar de nanosegundos: completar el resto exacto
+        assert_eq!(timestep.steps(FIXED_STEP - FIXED_STEP / 2), 1);
+        assert!(timestep.alpha() < 1e-3);
+        assert_eq!(timestep.steps(FIXED_STEP * 3 + FIXED_STEP / 4), 3);
+        assert!((timestep.alpha() - 0.25).abs() < 1e-3);
+    }
+
+    #[test]
+    fn fixed_timestep_clamps_long_frames() {
+        let mut timestep = FixedTimestep::new();
+        // Lo que no cabe en MAX_STEPS_PER_FRAME se descarta
+        assert_eq!(timestep.steps(Duration::from_secs(1)), MAX_STEPS_PER_FRAME);
+        assert_eq!(timestep.alpha(), 0.0);
+        assert_eq!(timestep.steps(Duration::ZERO), 0);
+
+        // Justo MAX_STEPS_PER_FRAME pasos y medio: se conserva el medio paso
+        let frame = FIXED_STEP * MAX_STEPS_PER_FRAME + FIXED_STEP / 2;
+        assert_eq!(timestep.steps(frame), MAX_STEPS_PER_FRAME);
+        assert!((timestep.alpha() - 0.5).abs() < 1e-3);
+    }
+
+    #[test]
+    fn fixed_timestep_total_does_not_depend_on_frame_split() {
+        let total = FIXED_STEP * 100;
+        let mut even = FixedTimestep::new();
+        let even_steps: u32 = (0..100).map(|_| even.steps(FIXED_STEP)).sum();
+
+        let mut uneven = FixedTimestep::new();
+        let frames = [3, 7, 1, 13, 5].map(|n| FIXED_STEP * n / 4);
+        let mut elapsed = Duration::ZERO;
+        let mut uneven_steps = 0;
+        for frame in frames.iter().cycle() {
+            let frame = (*frame).min(total - elapsed);
+            if frame.is_zero() {
+                break;
+            }
+            elapsed += frame;
+            uneven_steps += uneven.steps(frame);
+        }
+        assert_eq!(even_steps, 100);
+        assert_eq!(uneven_steps, 100);
+    }
+}
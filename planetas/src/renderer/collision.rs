@@ -0,0 +1,263 @@
+use glam::Vec3;
+use serde::Deserialize;
+
+use super::planets::Planet;
+
+/// Parámetros de las colisiones, configurables desde la escena (`collision`).
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct CollisionSettings {
+    /// Distancia mínima entre la nave (o la cámara libre) y cualquier superficie
+    pub margin: f32,
+    /// Fracción de la velocidad normal que se conserva al rebotar
+    pub restitution: f32,
+    /// Por debajo de esta velocidad de impacto la nave se desliza sin rebotar
+    pub bounce_speed: f32,
+    /// Por encima de esta velocidad de impacto el choque daña la nave
+    pub damage_speed: f32,
+}
+
+impl Default for CollisionSettings {
+    fn default() -> Self {
+        Self {
+            margin: 0.5,
+            restitution: 0.4,
+            bounce_speed: 2.0,
+            damage_speed: 12.0,
+        }
+    }
+}
+
+/// Parte de un cuerpo con la que se choca.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Surface {
+    Body,
+    Rings,
+}
+
+/// Contacto de un punto con la superficie más cercana que lo tiene dentro del margen.
+#[derive(Debug, Clone, Copy)]
+pub struct Contact {
+    pub body: usize,
+    pub surface: Surface,
+    /// Hacia fuera de la superficie
+    pub normal: Vec3,
+    /// Cuánto hay que empujar el punto por `normal` para dejarlo a `margin`
+    pub depth: f32,
+}
+
+/// Choque de la nave, para que el juego pueda reaccionar (HUD, sonido, daño...).
+#[derive(Debug, Clone, Copy)]
+pub struct CollisionEvent {
+    pub body: usize,
+    pub surface: Surface,
+    /// Velocidad contra la superficie en el momento del impacto
+    pub impact_speed: f32,
+    /// Daño recibido (0 si solo rebotó)
+    pub damage: f32,
+}
+
+/// Contacto más profundo de `point` con las esferas (radio real, lunas incluidas)
+/// y los anillos de todos los cuerpos, si alguno está a menos de `margin`.
+pub fn find_contact(planets: &[Planet], point: Vec3, margin: f32) -> Option<Contact> {
+    let mut deepest: Option<Contact> = None;
+
+    for (body, planet) in planets.iter().enumerate() {
+        let center = planet.get_position();
+        let offset = point - center;
+        let distance = offset.length();
+        let sphere = Contact {
+            body,
+            surface: Surface::Body,
+            normal: offset.try_normalize().unwrap_or(Vec3::Y),
+            depth: margin - (distance - planet.radius()),
+        };
+        let rings = planet.ring_annulus().and_then(|(normal, inner, outer)| {
+            annulus_contact(offset, normal, inner, outer, margin).map(|(normal, depth)| Contact {
+                body,
+                surface: Surface::Rings,
+                normal,
+                depth,
+            })
+        });
+
+        for contact in [Some(sphere), rings].into_iter().flatten() {
+            if contact.depth > 0.0 && deepest.is_none_or(|deepest| contact.depth > deepest.depth) {
+                deepest = Some(contact);
+            }
+        }
+    }
+
+    deepest
+}
+
+/// Distancia de `offset` (relativo al centro) al anillo plano entre `inner` y
+/// `outer` con normal `normal`: el punto más cercano del anillo es la proyección
+/// en su plano, con el radio limitado a [inner, outer].
+fn annulus_contact(offset: Vec3, normal: Vec3, inner: f32, outer: f32, margin: f32) -> Option<(Vec3, f32)> {
+    let height = offset.dot(normal);
+    let in_plane = offset - normal * height;
+    let radial = in_plane.length();
+    let closest = in_plane.try_normalize().unwrap_or(Vec3::ZERO) * radial.clamp(inner, outer);
+
+    let away = offset - closest;
+    let distance = away.length();
+    if distance >= margin {
+        return None;
+    }
+
+    let push = away.try_normalize().unwrap_or(if height < 0.0 { -normal } else { normal });
+    Some((push, margin - distance))
+}
+
+/// Saca `position` fuera de todas las superficies (hasta `margin`). Devuelve el
+/// último contacto resuelto, o None si no tocaba ninguna.
+pub fn resolve(planets: &[Planet], position: &mut Vec3, margin: f32) -> Option<Contact> {
+    let mut last = None;
+    // Un empujón puede meter el punto en otra superficie (por ejemplo entre el
+    // planeta y su anillo); unas pocas iteraciones bastan
+    for _ in 0..4 {
+        let Some(contact) = find_contact(planets, *position, margin) else {
+            break;
+        };
+        *position += contact.normal * contact.depth;
+        last = Some(contact);
+    }
+    last
+}
+
+/// Respuesta de la nave a un contacto: por debajo de `bounce_speed` se desliza
+/// por la superficie (se quita la velocidad normal); por encima rebota con
+/// `restitution`, y por encima de `damage_speed` además recibe daño.
+pub fn respond(velocity: &mut Vec3, contact: &Contact, settings: &CollisionSettings) -> Option<CollisionEvent> {
+    let into = velocity.dot(contact.normal);
+    if into >= 0.0 {
+        return None;
+    }
+    let impact_speed = -into;
+
+    if impact_speed < settings.bounce_speed {
+        *velocity -= contact.normal * into;
+        return None;
+    }
+
+    *velocity -= contact.normal * into * (1.0 + settings.restitution);
+    Some(CollisionEvent {
+        body: contact.body,
+        surface: contact.surface,
+        impact_speed,
+        damage: (impact_speed - settings.damage_speed).max(0.0),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glam::Mat3;
+
+    const MARGIN: f32 = 0.5;
+
+    /// Cuerpo de radio 2 (`scale` 1) colocado en `position`.
+    fn planet(position: Vec3, has_rings: bool) -> Planet {
+        let source = format!("(name: \"p\", planet_type: Disco, scale: 1.0, has_rings: {})", has_rings);
+        let mut planet: Planet = ron::from_str(&source).unwrap();
+        planet.place(position, None, 0.0);
+        planet
+    }
+
+    fn contact(normal: Vec3) -> Contact {
+        Contact { body: 0, surface: Surface::Body, normal, depth: 0.1 }
+    }
+
+    #[test]
+    fn sphere_depth_is_measured_from_the_surface() {
+        let planets = [planet(Vec3::new(10.0, 0.0, 0.0), false)];
+
+        let contact = find_contact(&planets, Vec3::new(10.0, 2.2, 0.0), MARGIN).unwrap();
+        assert_eq!(contact.surface, Surface::Body);
+        assert!(contact.normal.abs_diff_eq(Vec3::Y, 1e-5));
+        assert!((contact.depth - 0.3).abs() < 1e-5);
+
+        assert!(find_contact(&planets, Vec3::new(10.0, 2.6, 0.0), MARGIN).is_none());
+    }
+
+    #[test]
+    fn resolve_leaves_the_point_at_the_margin() {
+        let planets = [planet(Vec3::ZERO, false)];
+        let mut position = Vec3::new(0.0, 0.0, 1.0);
+        let contact = resolve(&planets, &mut position, MARGIN).unwrap();
+        assert_eq!(contact.body, 0);
+        assert!((position.length() - (2.0 + MARGIN)).abs() < 1e-4, "{:?}", position);
+    }
+
+    #[test]
+    fn annulus_pushes_out_of_its_plane() {
+        let above = annulus_contact(Vec3::new(3.0, 0.2, 0.0), Vec3::Y, 2.0, 4.0, MARGIN).unwrap();
+        assert!(above.0.abs_diff_eq(Vec3::Y, 1e-5));
+        assert!((above.1 - 0.3).abs() < 1e-5);
+
+        let below = annulus_contact(Vec3::new(0.0, -0.1, 3.0), Vec3::Y, 2.0, 4.0, MARGIN).unwrap();
+        assert!(below.0.abs_diff_eq(Vec3::NEG_Y, 1e-5));
+        assert!((below.1 - 0.4).abs() < 1e-5);
+
+        assert!(annulus_contact(Vec3::new(3.0, 0.6, 0.0), Vec3::Y, 2.0, 4.0, MARGIN).is_none());
+    }
+
+    #[test]
+    fn annulus_edges_push_radially() {
+        let outside = annulus_contact(Vec3::new(4.3, 0.0, 0.0), Vec3::Y, 2.0, 4.0, MARGIN).unwrap();
+        assert!(outside.0.abs_diff_eq(Vec3::X, 1e-5));
+        assert!((outside.1 - 0.2).abs() < 1e-5);
+
+        let inside = annulus_contact(Vec3::new(1.8, 0.0, 0.0), Vec3::Y, 2.0, 4.0, MARGIN).unwrap();
+        assert!(inside.0.abs_diff_eq(Vec3::NEG_X, 1e-5));
+        assert!((inside.1 - 0.3).abs() < 1e-5);
+
+        assert!(annulus_contact(Vec3::new(5.0, 0.0, 0.0), Vec3::Y, 2.0, 4.0, MARGIN).is_none());
+    }
+
+    #[test]
+    fn rings_are_found_in_their_tilted_plane() {
+        let planets = [planet(Vec3::ZERO, true)];
+        let (normal, inner, outer) = planets[0].ring_annulus().unwrap();
+        assert!(normal.abs_diff_eq(Mat3::from_rotation_x(0.3) * Vec3::Y, 1e-5));
+
+        // A medio anillo y 0.1 por encima de su plano; la esfera queda a más del margen
+        let radius = (inner + outer) / 2.0;
+        let point = Mat3::from_rotation_x(0.3) * Vec3::new(0.0, 0.1, radius);
+        let contact = find_contact(&planets, point, MARGIN).unwrap();
+        assert_eq!(contact.surface, Surface::Rings);
+        assert!(contact.normal.abs_diff_eq(normal, 1e-4));
+        assert!((contact.depth - 0.4).abs() < 1e-4);
+    }
+
+    #[test]
+    fn slow_impacts_slide() {
+        let settings = CollisionSettings::default();
+        let mut velocity = Vec3::new(1.0, -1.0, 0.0);
+        assert!(respond(&mut velocity, &contact(Vec3::Y), &settings).is_none());
+        assert!(velocity.abs_diff_eq(Vec3::X, 1e-5));
+
+        // Alejándose de la superficie no se toca la velocidad
+        let mut velocity = Vec3::new(0.0, 3.0, 0.0);
+        assert!(respond(&mut velocity, &contact(Vec3::Y), &settings).is_none());
+        assert_eq!(velocity, Vec3::new(0.0, 3.0, 0.0));
+    }
+
+    #[test]
+    fn fast_impacts_bounce_and_damage() {
+        let settings = CollisionSettings::default();
+
+        // Justo en `bounce_speed` ya rebota, con `restitution`
+        let mut velocity = Vec3::new(0.0, -settings.bounce_speed, 0.0);
+        let event = respond(&mut velocity, &contact(Vec3::Y), &settings).unwrap();
+        assert!(velocity.abs_diff_eq(Vec3::new(0.0, settings.bounce_speed * settings.restitution, 0.0), 1e-5));
+        assert_eq!(event.damage, 0.0);
+
+        let mut velocity = Vec3::new(0.0, -15.0, 0.0);
+        let event = respond(&mut velocity, &contact(Vec3::Y), &settings).unwrap();
+        assert!((event.impact_speed - 15.0).abs() < 1e-5);
+        assert!((event.damage - (15.0 - settings.damage_speed)).abs() < 1e-5);
+        assert!(velocity.abs_diff_eq(Vec3::new(0.0, 15.0 * settings.restitution, 0.0), 1e-4));
+    }
+}
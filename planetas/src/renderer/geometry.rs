@@ -1,5 +1,11 @@
+use glam::{Vec2, Vec3, Vec4};
+
+use super::orbit::OrbitalElements;
 use super::types::Vertex;
 
+/// Esfera UV con el eje de los polos en Y. La U da la vuelta al ecuador desde +X
+/// hacia +Z y la V va del polo norte (0) al sur (1), como el `atan2(z, x)` y
+/// `acos(y)` con los que los shaders pintaban antes sus patrones.
 pub fn create_sphere(radius: f32, sectors: u32, stacks: u32) -> (Vec<Vertex>, Vec<u32>) {
     let mut vertices = Vec::new();
     let mut indices = Vec::new();
@@ -9,97 +15,196 @@ pub fn create_sphere(radius: f32, sectors: u32, stacks: u32) -> (Vec<Vertex>, Ve
 
     for i in 0..=stacks {
         let stack_angle = std::f32::consts::PI / 2.0 - i as f32 * stack_step;
-        let xy = radius * stack_angle.cos();
-        let z = radius * stack_angle.sin();
+        let xz = stack_angle.cos();
+        let y = stack_angle.sin();
 
         for j in 0..=sectors {
             let sector_angle = j as f32 * sector_step;
-            let x = xy * sector_angle.cos();
-            let y = xy * sector_angle.sin();
+            let (sin_s, cos_s) = sector_angle.sin_cos();
+            let normal = [xz * cos_s, y, xz * sin_s];
 
             vertices.push(Vertex {
-                position: [x, y, z],
+                position: normal.map(|n| n * radius),
+                normal,
+                uv: [j as f32 / sectors as f32, i as f32 / stacks as f32],
+                tangent: [-sin_s, 0.0, cos_s, 1.0],
             });
         }
     }
 
     for i in 0..stacks {
-        let mut k1 = i * (sectors + 1);
-        let mut k2 = k1 + sectors + 1;
+        for j in 0..sectors {
+            let k1 = i * (sectors + 1) + j;
+            let k2 = k1 + sectors + 1;
 
-        for _ in 0..sectors {
             if i != 0 {
                 indices.push(k1);
-                indices.push(k2);
                 indices.push(k1 + 1);
+                indices.push(k2);
             }
 
             if i != (stacks - 1) {
                 indices.push(k1 + 1);
-                indices.push(k2);
                 indices.push(k2 + 1);
+                indices.push(k2);
             }
-
-            k1 += 1;
-            k2 += 1;
         }
     }
 
     (vertices, indices)
 }
 
+/// Corona plana en el plano XZ con la normal hacia +Y. La U da la vuelta (0 a 1)
+/// y la V va del borde interior (0) al exterior (1).
 pub fn create_ring(inner_radius: f32, outer_radius: f32, segments: u32) -> (Vec<Vertex>, Vec<u32>) {
     let mut vertices = Vec::new();
     let mut indices = Vec::new();
 
-    for i in 0..segments {
-        let theta = (i as f32) * (2.0 * std::f32::consts::PI) / segments as f32;
+    // Un par de vértices más para cerrar la costura de la U
+    for i in 0..=segments {
+        let u = i as f32 / segments as f32;
+        let theta = u * 2.0 * std::f32::consts::PI;
         let cos_t = theta.cos();
         let sin_t = theta.sin();
+        let tangent = [-sin_t, 0.0, cos_t, 1.0];
 
         // inner then outer vertex per segment
-        vertices.push(Vertex { position: [inner_radius * cos_t, 0.0, inner_radius * sin_t] });
-        vertices.push(Vertex { position: [outer_radius * cos_t, 0.0, outer_radius * sin_t] });
+        for (radius, v) in [(inner_radius, 0.0), (outer_radius, 1.0)] {
+            vertices.push(Vertex {
+                position: [radius * cos_t, 0.0, radius * sin_t],
+                normal: [0.0, 1.0, 0.0],
+                uv: [u, v],
+                tangent,
+            });
+        }
     }
 
     // Create two triangles per segment
     for i in 0..segments {
-        let next = (i + 1) % segments;
         let i0 = i * 2;
         let i1 = i0 + 1;
-        let n0 = next * 2;
+        let n0 = i0 + 2;
         let n1 = n0 + 1;
 
-        indices.push(i0 as u32);
-        indices.push(n0 as u32);
-        indices.push(i1 as u32);
+        indices.push(i0);
+        indices.push(n0);
+        indices.push(i1);
 
-        indices.push(i1 as u32);
-        indices.push(n0 as u32);
-        indices.push(n1 as u32);
+        indices.push(i1);
+        indices.push(n0);
+        indices.push(n1);
     }
 
     (vertices, indices)
 }
 
-pub fn create_orbit(radius: f32, segments: u32, inclination: f32) -> (Vec<Vertex>, Vec<u32>) {
+/// Malla con sombreado plano: cada triángulo lleva sus propios vértices con la
+/// normal de la cara (según su orden antihorario), la UV que da `uv` para cada
+/// posición y la tangente que sale de esas UV.
+pub fn flat_shaded(positions: &[Vec3], triangles: &[[u32; 3]], uv: impl Fn(Vec3) -> Vec2) -> (Vec<Vertex>, Vec<u32>) {
+    let mut vertices = Vec::with_capacity(triangles.len() * 3);
+
+    for triangle in triangles {
+        let corners = triangle.map(|index| positions[index as usize]);
+        let uvs = corners.map(&uv);
+        let normal = (corners[1] - corners[0]).cross(corners[2] - corners[0]).normalize_or_zero();
+        let tangent = triangle_tangent(corners, uvs, normal);
+
+        for (position, uv) in corners.iter().zip(uvs) {
+            vertices.push(Vertex {
+                position: position.to_array(),
+                normal: normal.to_array(),
+                uv: uv.to_array(),
+                tangent,
+            });
+        }
+    }
+
+    let indices = (0..vertices.len() as u32).collect();
+    (vertices, indices)
+}
+
+/// Normales suaves para mallas importadas sin ellas: suma de las normales de
+/// los triángulos que comparten cada vértice, ponderadas por su área.
+pub fn generate_normals(vertices: &mut [Vertex], indices: &[u32]) {
+    let mut normals = vec![Vec3::ZERO; vertices.len()];
+    for triangle in indices.chunks_exact(3) {
+        let [a, b, c] = [0, 1, 2].map(|k| Vec3::from(vertices[triangle[k] as usize].position));
+        let normal = (b - a).cross(c - a);
+        for &index in triangle {
+            normals[index as usize] += normal;
+        }
+    }
+    for (vertex, normal) in vertices.iter_mut().zip(normals) {
+        vertex.normal = normal.normalize_or(Vec3::Y).to_array();
+    }
+}
+
+/// Tangentes por vértice a partir de las UV: se suman las de los triángulos que
+/// lo comparten y se usa el signo de la mayoría.
+pub fn generate_tangents(vertices: &mut [Vertex], indices: &[u32]) {
+    let mut tangents = vec![Vec4::ZERO; vertices.len()];
+    for triangle in indices.chunks_exact(3) {
+        let corners = [0, 1, 2].map(|k| Vec3::from(vertices[triangle[k] as usize].position));
+        let uvs = [0, 1, 2].map(|k| Vec2::from(vertices[triangle[k] as usize].uv));
+        let normal = (corners[1] - corners[0]).cross(corners[2] - corners[0]).normalize_or_zero();
+        let tangent = Vec4::from(triangle_tangent(corners, uvs, normal));
+        for &index in triangle {
+            tangents[index as usize] += tangent;
+        }
+    }
+    for (vertex, tangent) in vertices.iter_mut().zip(tangents) {
+        let normal = Vec3::from(vertex.normal);
+        let direction = tangent.truncate() - normal * normal.dot(tangent.truncate());
+        let direction = direction.try_normalize().unwrap_or_else(|| normal.any_orthonormal_vector());
+        let handedness = if tangent.w < 0.0 { -1.0 } else { 1.0 };
+        vertex.tangent = direction.extend(handedness).to_array();
+    }
+}
+
+/// Tangente de un triángulo a partir de sus UV, ortogonalizada con la normal.
+/// Si las UV no cubren área (proyección de canto) se usa cualquier perpendicular.
+fn triangle_tangent(corners: [Vec3; 3], uvs: [Vec2; 3], normal: Vec3) -> [f32; 4] {
+    let edge1 = corners[1] - corners[0];
+    let edge2 = corners[2] - corners[0];
+    let duv1 = uvs[1] - uvs[0];
+    let duv2 = uvs[2] - uvs[0];
+
+    let det = duv1.x * duv2.y - duv2.x * duv1.y;
+    if det.abs() < 1e-8 {
+        return normal.any_orthonormal_vector().extend(1.0).to_array();
+    }
+    let tangent = (edge1 * duv2.y - edge2 * duv1.y) / det;
+    let bitangent = (edge2 * duv1.x - edge1 * duv2.x) / det;
+
+    let Some(tangent) = (tangent - normal * normal.dot(tangent)).try_normalize() else {
+        return normal.any_orthonormal_vector().extend(1.0).to_array();
+    };
+    let handedness = if normal.cross(tangent).dot(bitangent) < 0.0 { -1.0 } else { 1.0 };
+    tangent.extend(handedness).to_array()
+}
+
+/// Elipse de la órbita en el marco del padre, muestreada por anomalía excéntrica
+/// con la misma función que posiciona al cuerpo.
+pub fn create_orbit(orbit: &OrbitalElements, segments: u32) -> (Vec<Vertex>, Vec<u32>) {
     let mut vertices = Vec::new();
     let mut indices = Vec::new();
 
     for i in 0..segments {
-        let theta = (i as f32) * (2.0 * std::f32::consts::PI) / segments as f32;
-        let x = radius * theta.cos();
-        let z = radius * theta.sin();
-        let y = theta.sin() * radius * inclination;
-        vertices.push(Vertex { position: [x, y, z] });
+        let eccentric_anomaly = (i as f32) * (2.0 * std::f32::consts::PI) / segments as f32;
+        let position = orbit.position_from_eccentric_anomaly(eccentric_anomaly);
+        vertices.push(Vertex {
+            position: position.to_array(),
+            ..Default::default()
+        });
     }
 
     // Line segments as index pairs for each edge
     for i in 0..segments {
         let next = (i + 1) % segments;
-        indices.push(i as u32);
-        indices.push(next as u32);
+        indices.push(i);
+        indices.push(next);
     }
 
     (vertices, indices)
-}
\ No newline at end of file
+}
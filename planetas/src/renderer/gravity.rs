@@ -0,0 +1,166 @@
+use glam::Vec3;
+
+use super::planets::Planet;
+
+/// Densidad (G·M por unidad de radio al cubo) de los cuerpos sin `mass` que
+/// tampoco tienen nada orbitando a su alrededor.
+const DEFAULT_DENSITY: f32 = 1.0;
+/// Suavizado de la fuerza a distancias muy cortas, para que dos cuerpos que se
+/// cruzan no salgan disparados.
+const SOFTENING: f32 = 0.5;
+/// Subpaso máximo del integrador en segundos de simulación.
+const MAX_SUBSTEP: f32 = 1.0 / 240.0;
+/// Con escalas de tiempo muy altas se integra con subpasos más largos (y menos
+/// precisos) en lugar de congelar el programa.
+const MAX_SUBSTEPS: u32 = 512;
+
+/// Modo gravedad: los cuerpos dejan sus órbitas analíticas y se mueven por la
+/// atracción mutua de todos ellos, integrada con Velocity Verlet.
+///
+/// Cada cuerpo tiene un parámetro gravitacional (G·M): el `mass` de la escena,
+/// o si falta el que hace falta para que sus satélites den la vuelta a la
+/// velocidad de su órbita (n²a³), o si no tiene satélites uno proporcional a su
+/// volumen.
+pub struct NBody {
+    positions: Vec<Vec3>,
+    velocities: Vec<Vec3>,
+    accelerations: Vec<Vec3>,
+    /// Posiciones al empezar el último `step`, para interpolar al dibujar
+    previous: Vec<Vec3>,
+    mu: Vec<f32>,
+}
+
+impl NBody {
+    /// Arranca desde las posiciones de las órbitas analíticas en `time`. Cada
+    /// cuerpo sale en la dirección de su órbita con la velocidad que le daría
+    /// la gravedad de su centro (vis-viva), sumada a la de ese centro.
+    pub fn from_orbits(planets: &mut [Planet], time: f32) -> Self {
+        super::planets::update_system(planets, time);
+
+        let attractors: Vec<Option<usize>> = (0..planets.len()).map(|i| attractor(planets, i)).collect();
+        let mu: Vec<f32> = (0..planets.len()).map(|i| gravitational_parameter(planets, &attractors, i)).collect();
+        let positions: Vec<Vec3> = planets.iter().map(|planet| planet.get_position()).collect();
+
+        let mut velocities = vec![Vec3::ZERO; planets.len()];
+        // Los padres van antes que sus hijos, pero el centro de un cuerpo de la
+        // raíz puede ir después; se resuelven primero los que no tienen órbita
+        for i in (0..planets.len()).filter(|&i| planets[i].orbit.is_none()) {
+            velocities[i] = planets[i].parent.map_or(Vec3::ZERO, |parent| velocities[parent]);
+        }
+        for i in (0..planets.len()).filter(|&i| planets[i].orbit.is_some()) {
+            let orbit = planets[i].orbit.as_ref().unwrap();
+            let h = 1e-3;
+            let tangent = (orbit.position_at(time + h) - orbit.position_at(time - h)) / (2.0 * h);
+
+            let relative = match attractors[i] {
+                Some(center) => {
+                    let r = (positions[i] - positions[center]).length();
+                    let speed_squared = mu[center] * (2.0 / r - 1.0 / orbit.semi_major_axis);
+                    tangent.normalize_or_zero() * speed_squared.max(0.0).sqrt()
+                }
+                None => tangent,
+            };
+            let center_velocity = attractors[i].or(planets[i].parent).map_or(Vec3::ZERO, |center| velocities[center]);
+            velocities[i] = center_velocity + relative;
+        }
+
+        // Sin momento total, para que el sistema no se vaya desplazando
+        let total_mu: f32 = mu.iter().sum();
+        let drift = velocities.iter().zip(&mu).map(|(velocity, mu)| *velocity * *mu).sum::<Vec3>() / total_mu;
+        for velocity in &mut velocities {
+            *velocity -= drift;
+        }
+
+        let mut nbody = Self {
+            previous: positions.clone(),
+            accelerations: Vec::new(),
+            positions,
+            velocities,
+            mu,
+        };
+        nbody.accelerations = nbody.compute_accelerations();
+        nbody
+    }
+
+    /// Avanza `dt` segundos de simulación (negativo hacia atrás: Verlet es reversible).
+    pub fn step(&mut self, dt: f32) {
+        self.previous.clone_from(&self.positions);
+        if dt == 0.0 {
+            return;
+        }
+
+        let substeps = ((dt.abs() / MAX_SUBSTEP).ceil() as u32).clamp(1, MAX_SUBSTEPS);
+        let h = dt / substeps as f32;
+        for _ in 0..substeps {
+            for i in 0..self.positions.len() {
+                self.velocities[i] += self.accelerations[i] * (h * 0.5);
+                self.positions[i] += self.velocities[i] * h;
+            }
+            self.accelerations = self.compute_accelerations();
+            for i in 0..self.positions.len() {
+                self.velocities[i] += self.accelerations[i] * (h * 0.5);
+            }
+        }
+    }
+
+    /// Coloca los cuerpos entre el paso anterior (`alpha` = 0) y el actual (`alpha` = 1).
+    /// La rotación propia sigue el reloj, como en el modo analítico.
+    pub fn apply(&self, planets: &mut [Planet], alpha: f32, time: f32) {
+        for i in 0..planets.len() {
+            let position = self.previous[i].lerp(self.positions[i], alpha);
+            let parent_position = planets[i].parent.map(|parent| planets[parent].get_position());
+            planets[i].place(position, parent_position, time);
+        }
+    }
+
+    /// Aceleración de la gravedad de todos los cuerpos en `point`.
+    pub fn acceleration_at(&self, point: Vec3) -> Vec3 {
+        self.positions.iter().zip(&self.mu).map(|(position, mu)| attraction(point, *position, *mu)).sum()
+    }
+
+    fn compute_accelerations(&self) -> Vec<Vec3> {
+        (0..self.positions.len())
+            .map(|i| {
+                (0..self.positions.len())
+                    .filter(|&j| j != i)
+                    .map(|j| attraction(self.positions[i], self.positions[j], self.mu[j]))
+                    .sum()
+            })
+            .collect()
+    }
+}
+
+fn attraction(point: Vec3, body: Vec3, mu: f32) -> Vec3 {
+    let offset = body - point;
+    let distance_squared = offset.length_squared() + SOFTENING * SOFTENING;
+    offset * (mu / (distance_squared * distance_squared.sqrt()))
+}
+
+/// Cuerpo alrededor del que orbita `index`: su padre, o para los de la raíz el
+/// primer cuerpo de la raíz fijo en el origen (el sol), si lo hay.
+fn attractor(planets: &[Planet], index: usize) -> Option<usize> {
+    planets[index].orbit.as_ref()?;
+    planets[index].parent.or_else(|| {
+        planets
+            .iter()
+            .position(|planet| planet.parent.is_none() && planet.orbit.is_none())
+    })
+}
+
+fn gravitational_parameter(planets: &[Planet], attractors: &[Option<usize>], index: usize) -> f32 {
+    if let Some(mass) = planets[index].mass {
+        return mass;
+    }
+
+    let satellites: Vec<f32> = (0..planets.len())
+        .filter(|&i| attractors[i] == Some(index))
+        .filter_map(|i| planets[i].orbit.as_ref())
+        .filter(|orbit| orbit.mean_motion != 0.0)
+        .map(|orbit| orbit.mean_motion.powi(2) * orbit.semi_major_axis.powi(3))
+        .collect();
+    if satellites.is_empty() {
+        DEFAULT_DENSITY * planets[index].radius().powi(3)
+    } else {
+        satellites.iter().sum::<f32>() / satellites.len() as f32
+    }
+}
@@ -0,0 +1,257 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use notify::{EventKind, RecursiveMode, Watcher};
+
+use super::pipelines::{
+    self, ShaderLibrary, ORBIT_SHADER, PLANET_SHADERS, POST_SHADER, PROP_SHADER, RING_SHADER,
+    SHADOW_SHADER, SHIP_SHADER, SKYBOX_SHADER, TEXT_SHADER,
+};
+use super::postprocess::HDR_FORMAT;
+use super::types::RendererState;
+
+/// Tiempo sin eventos antes de releer un archivo: los editores suelen guardar
+/// en varios pasos (vaciar, escribir, renombrar) y no queremos leerlo a medias.
+const SETTLE_TIME: Duration = Duration::from_millis(100);
+
+/// Vigila los `.wgsl` de un directorio y dice cuáles cambiaron.
+pub struct ShaderWatcher {
+    dir: PathBuf,
+    // Se guarda solo para que siga vigilando mientras viva
+    _watcher: notify::RecommendedWatcher,
+    events: mpsc::Receiver<notify::Result<notify::Event>>,
+    /// Archivos con cambios y momento del último evento de cada uno
+    pending: HashMap<String, Instant>,
+}
+
+impl ShaderWatcher {
+    pub fn new(dir: PathBuf) -> notify::Result<Self> {
+        let (sender, events) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(sender)?;
+        watcher.watch(&dir, RecursiveMode::NonRecursive)?;
+        Ok(Self { dir, _watcher: watcher, events, pending: HashMap::new() })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Nombres de los archivos que cambiaron y ya llevan `SETTLE_TIME` quietos.
+    pub fn changed_files(&mut self) -> Vec<String> {
+        let now = Instant::now();
+        for event in self.events.try_iter() {
+            let event = match event {
+                Ok(event) => event,
+                Err(err) => {
+                    println!("[shaders] error vigilando {}: {}", self.dir.display(), err);
+                    continue;
+                }
+            };
+            if !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+                continue;
+            }
+            for path in event.paths {
+                if path.extension().is_some_and(|ext| ext == "wgsl") {
+                    if let Some(name) = path.file_name().and_then(|name| name.to_str()) {
+                        self.pending.insert(name.to_string(), now);
+                    }
+                }
+            }
+        }
+
+        let settled: Vec<String> = self.pending.iter()
+            .filter(|(_, last)| now.duration_since(**last) >= SETTLE_TIME)
+            .map(|(name, _)| name.clone())
+            .collect();
+        for name in &settled {
+            self.pending.remove(name);
+        }
+        settled
+    }
+}
+
+/// Crea un pipeline capturando los errores de validación de wgpu (WGSL que no
+/// compila, entry points o bindings que no cuadran) en vez de abortar.
+fn compile<T>(
+    device: &wgpu::Device,
+    shaders: &ShaderLibrary,
+    label: &str,
+    files: &[&str],
+    create: impl FnOnce() -> T,
+) -> Option<T> {
+    device.push_error_scope(wgpu::ErrorFilter::Validation);
+    let value = create();
+    match pollster::block_on(device.pop_error_scope()) {
+        None => Some(value),
+        Some(err) => {
+            println!(
+                "[shaders] {}: error, se mantiene el pipeline anterior ({})\n{}",
+                label,
+                shaders.line_ranges(files),
+                err,
+            );
+            None
+        }
+    }
+}
+
+impl RendererState {
+    /// Lee de `dir` los shaders que difieran de las copias embebidas y recrea
+    /// todos los pipelines si alguno cambió.
+    pub fn reload_all_shaders(&mut self, dir: &Path) {
+        let mut changed = false;
+        for file in ShaderLibrary::files() {
+            match self.shaders.reload(dir, file) {
+                Ok(file_changed) => changed |= file_changed,
+                Err(err) => println!("[shaders] no se pudo leer {}: {}", file, err),
+            }
+        }
+        if changed {
+            self.rebuild_pipelines("src/shaders", |_| true);
+        }
+    }
+
+    /// Relee `file` de `dir` y recrea los pipelines que lo usan.
+    pub fn reload_shader(&mut self, dir: &Path, file: &str) {
+        match self.shaders.reload(dir, file) {
+            Ok(true) => self.rebuild_pipelines(file, |files| files.contains(&file)),
+            Ok(false) => {}
+            Err(err) => println!("[shaders] no se pudo leer {}: {}", file, err),
+        }
+    }
+
+    /// Recrea los pipelines cuyos archivos cumplen `uses`. Los que fallan
+    /// conservan el pipeline anterior.
+    fn rebuild_pipelines(&mut self, what: &str, uses: impl Fn(&[&str]) -> bool) {
+        let device = &self.device;
+        let shaders = &self.shaders;
+        let (mut rebuilt, mut failed) = (0, 0);
+        let mut count = |ok: bool| if ok { rebuilt += 1 } else { failed += 1 };
+
+        for (index, (label, files)) in PLANET_SHADERS.iter().enumerate() {
+            if uses(files) {
+                let pipeline = compile(device, shaders, label, files, || pipelines::create_planet_pipeline(
+                    device,
+                    shaders,
+                    index,
+                    &self.uniform_bind_group_layout,
+                    &self.light_bind_group_layout,
+                    &self.material_bind_group_layout,
+                    HDR_FORMAT,
+                ));
+                count(pipeline.is_some());
+                if let Some(pipeline) = pipeline {
+                    self.planet_pipelines[index] = pipeline;
+                }
+            }
+        }
+
+        if uses(&SHIP_SHADER) {
+            let pipeline = compile(device, shaders, "Ship Shader", &SHIP_SHADER, || pipelines::create_ship_pipeline(
+                device,
+                shaders,
+                &self.uniform_bind_group_layout,
+                &self.light_bind_group_layout,
+                HDR_FORMAT,
+            ));
+            count(pipeline.is_some());
+            if let Some(pipeline) = pipeline {
+                self.ship_pipeline = pipeline;
+            }
+        }
+
+        if uses(&PROP_SHADER) {
+            let pipeline = compile(device, shaders, "Prop Shader", &PROP_SHADER, || pipelines::create_prop_pipeline(
+                device,
+                shaders,
+                &self.uniform_bind_group_layout,
+                &self.light_bind_group_layout,
+                &self.material_bind_group_layout,
+                HDR_FORMAT,
+            ));
+            count(pipeline.is_some());
+            if let Some(pipeline) = pipeline {
+                self.prop_pipeline = pipeline;
+            }
+        }
+
+        if uses(&SKYBOX_SHADER) {
+            let pipeline = compile(device, shaders, "Skybox Shader", &SKYBOX_SHADER, || pipelines::create_skybox_pipeline(
+                device,
+                shaders,
+                &self.uniform_bind_group_layout,
+                self.sky.as_ref().map(|_| &self.sky_bind_group_layout),
+                HDR_FORMAT,
+            ));
+            count(pipeline.is_some());
+            if let Some(pipeline) = pipeline {
+                self.skybox_pipeline = pipeline;
+            }
+        }
+
+        if uses(&RING_SHADER) {
+            let pipeline = compile(device, shaders, "Ring Shader", &RING_SHADER, || pipelines::create_ring_pipeline(
+                device,
+                shaders,
+                &self.uniform_bind_group_layout,
+                &self.light_bind_group_layout,
+                HDR_FORMAT,
+            ));
+            count(pipeline.is_some());
+            if let Some(pipeline) = pipeline {
+                self.ring_pipeline = pipeline;
+            }
+        }
+
+        if uses(&ORBIT_SHADER) {
+            let pipeline = compile(device, shaders, "Orbit Shader", &ORBIT_SHADER, || pipelines::create_orbit_pipeline(
+                device,
+                shaders,
+                &self.uniform_bind_group_layout,
+                HDR_FORMAT,
+            ));
+            count(pipeline.is_some());
+            if let Some(pipeline) = pipeline {
+                self.orbit_pipeline = pipeline;
+            }
+        }
+
+        if uses(&SHADOW_SHADER) {
+            let pipeline = compile(device, shaders, "Shadow Shader", &SHADOW_SHADER, || pipelines::create_shadow_pipeline(
+                device,
+                shaders,
+                &self.uniform_bind_group_layout,
+                &self.shadow_map.face_bind_group_layout,
+            ));
+            count(pipeline.is_some());
+            if let Some(pipeline) = pipeline {
+                self.shadow_pipeline = pipeline;
+            }
+        }
+
+        if uses(&POST_SHADER) {
+            let post = compile(device, shaders, "Post Process Shader", &POST_SHADER, || self.post.create_pipelines(device, shaders));
+            count(post.is_some());
+            if let Some(post) = post {
+                self.post.set_pipelines(post);
+            }
+        }
+
+        if uses(&TEXT_SHADER) {
+            let pipeline = compile(device, shaders, "Text Shader", &TEXT_SHADER, || self.text.create_pipeline(device, shaders));
+            count(pipeline.is_some());
+            if let Some(pipeline) = pipeline {
+                self.text.set_pipeline(pipeline);
+            }
+        }
+
+        if rebuilt + failed == 0 {
+            // sky_bake.wgsl solo se usa al generar el cubemap del cielo
+            println!("[shaders] {}: no hay pipelines que recargar, se aplica al reiniciar", what);
+        } else {
+            println!("[shaders] {}: recompilados {}, con errores {}", what, rebuilt, failed);
+        }
+    }
+}
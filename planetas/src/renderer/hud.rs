@@ -0,0 +1,281 @@
+use glam::Vec3;
+
+use super::picking;
+use super::text::{TextRenderer, GLYPH_SIZE};
+use super::Renderer;
+use crate::flight::ShipView;
+
+const TEXT_SCALE: f32 = 2.0;
+const LINE_HEIGHT: f32 = GLYPH_SIZE * TEXT_SCALE + 4.0;
+const MARGIN: f32 = 12.0;
+const PADDING: f32 = 8.0;
+
+const TEXT_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+const KEY_COLOR: [f32; 4] = [0.3, 0.9, 1.0, 1.0];
+const PANEL_COLOR: [f32; 4] = [0.0, 0.0, 0.0, 0.55];
+const LABEL_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.7];
+const SELECTED_COLOR: [f32; 4] = [1.0, 0.85, 0.2, 1.0];
+const ALERT_COLOR: [f32; 4] = [1.0, 0.4, 0.3, 1.0];
+/// Segundos que se muestra un aviso.
+const NOTICE_DURATION: f32 = 2.0;
+
+/// Estado del HUD: visibilidad, medición de FPS y aviso temporal.
+pub struct Hud {
+    pub visible: bool,
+    pub show_help: bool,
+    pub labels: bool,
+    /// Aviso centrado arriba (por ejemplo un choque) y segundos que le quedan
+    notice: Option<(String, f32)>,
+    fps: f32,
+    fps_frames: u32,
+    fps_elapsed: f32,
+}
+
+impl Hud {
+    pub fn new() -> Self {
+        Self {
+            visible: true,
+            show_help: false,
+            labels: true,
+            notice: None,
+            fps: 0.0,
+            fps_frames: 0,
+            fps_elapsed: 0.0,
+        }
+    }
+
+    /// Muestra `text` durante `NOTICE_DURATION` segundos, sustituyendo al aviso anterior.
+    pub fn notify(&mut self, text: String) {
+        self.notice = Some((text, NOTICE_DURATION));
+    }
+
+    /// Acumula frames y recalcula los FPS cada medio segundo.
+    pub fn update(&mut self, dt: f32) {
+        if let Some((_, remaining)) = &mut self.notice {
+            *remaining -= dt;
+            if *remaining <= 0.0 {
+                self.notice = None;
+            }
+        }
+
+        self.fps_frames += 1;
+        self.fps_elapsed += dt;
+        if self.fps_elapsed >= 0.5 {
+            self.fps = self.fps_frames as f32 / self.fps_elapsed;
+            self.fps_frames = 0;
+            self.fps_elapsed = 0.0;
+        }
+    }
+}
+
+impl Renderer {
+    /// Encola el HUD del frame actual: etiquetas de los cuerpos, estadísticas
+    /// arriba a la izquierda, ficha del cuerpo seleccionado abajo y, si está
+    /// activa, la ayuda de teclas a la derecha.
+    pub(super) fn queue_hud(&mut self) {
+        if !self.hud.visible {
+            return;
+        }
+
+        self.queue_labels();
+        if self.mouse_look {
+            self.queue_crosshair();
+        }
+
+        let mode = if let (Some(camera_path), Some(time)) = (&self.camera_path, self.path_time) {
+            format!("camino - {} {:.1}/{:.1} s", camera_path.name, time, camera_path.duration())
+        } else if self.is_warping {
+            "warp".to_string()
+        } else if let Some(orbit) = &self.orbit {
+            format!("órbita - {}", self.planets[orbit.body].name)
+        } else {
+            match self.ship_view {
+                Some(ShipView::Chase) => "nave - tercera persona".to_string(),
+                Some(ShipView::Cockpit) => "nave - cabina".to_string(),
+                None => "vuelo libre".to_string(),
+            }
+        };
+        let warp = match &self.current_warp {
+            Some(warp) if self.is_warping => format!("{} (en curso)", warp.name),
+            Some(warp) => warp.name.clone(),
+            None => "-".to_string(),
+        };
+        let position = self.camera.position;
+
+        let stats = [
+            format!("FPS {:.0}", self.hud.fps),
+            format!("Pos ({:.1}, {:.1}, {:.1})", position.x, position.y, position.z),
+            match self.ship_view {
+                Some(_) => format!("Vel {:.1}{}", self.ship.speed(), if self.boost { " (turbo)" } else { "" }),
+                None => format!("Vel {:.1}", self.camera.speed),
+            },
+            format!("Casco {:.0}%", self.ship.hull),
+            format!("Modo {}", mode),
+            format!("Warp {}", warp),
+            format!("Tiempo {:.1} s x{}{}", self.clock.time, format_scale(self.clock.scale), if self.clock.paused { " (pausa)" } else { "" }),
+            match &self.shader_clock {
+                Some(clock) => format!("Shaders {:.1} s x{}", clock.time, format_scale(clock.scale)),
+                None => "Shaders enlazados".to_string(),
+            },
+            format!("Orbitas {}", if self.gravity.is_some() { "gravedad N-cuerpos" } else { "analíticas" }),
+        ];
+        let hint = "H: ayuda";
+        let hint_y = self.size.height as f32 - MARGIN - LINE_HEIGHT;
+        let info = self.selected.map(|body| self.body_info(body));
+
+        let text = &mut self.state.text;
+        queue_panel(text, MARGIN, MARGIN, &stats);
+        text.queue_text(hint, MARGIN, hint_y, TEXT_SCALE, KEY_COLOR);
+
+        if let Some((notice, _)) = &self.hud.notice {
+            let width = notice.chars().count() as f32 * GLYPH_SIZE * TEXT_SCALE;
+            text.queue_text(notice, (self.size.width as f32 - width) * 0.5, MARGIN * 4.0, TEXT_SCALE, ALERT_COLOR);
+        }
+
+        if let Some(info) = info {
+            let height = info.len() as f32 * LINE_HEIGHT + PADDING * 2.0 - 4.0;
+            queue_panel(text, MARGIN, hint_y - MARGIN - height, &info);
+        }
+
+        if self.hud.show_help {
+            let mut help: Vec<(String, &str)> = vec![
+                ("WASD Esp Shift".into(), "mover / empujar la nave"),
+                ("Flechas  Q E".into(), "girar / alabear"),
+                ("Tab  F2".into(), "turbo / tercera persona, cabina, libre"),
+                ("Clic / Esc".into(), "seleccionar o capturar / liberar ratón"),
+                ("G  Retroceso".into(), "warp a la selección / deseleccionar"),
+                ("L".into(), "mostrar / ocultar etiquetas"),
+                ("Rueda".into(), "velocidad (Ctrl: FOV)"),
+                ("F  [ ]".into(), "cámara orbital (selección) / cambiar cuerpo"),
+                ("T B - =".into(), "tonemapper, bloom, exposición"),
+                ("C  V".into(), "reproducir camino / bucle"),
+                ("P  N".into(), "pausa / avanzar un frame"),
+                ("R  RePág AvPág".into(), "invertir / acelerar / frenar el tiempo"),
+                ("Inicio".into(), "volver a t = 0"),
+                ("K".into(), "separar / enlazar el tiempo de los shaders"),
+                ("M".into(), "gravedad N-cuerpos / órbitas analíticas"),
+                ("H".into(), "mostrar / ocultar ayuda"),
+                ("F1".into(), "mostrar / ocultar HUD"),
+            ];
+            for (i, warp) in self.warp_points.iter().enumerate() {
+                help.push(((i + 1).to_string(), warp.name.as_str()));
+            }
+
+            let key_columns = help.iter().map(|(key, _)| key.chars().count()).max().unwrap_or(0) + 2;
+            let key_width = key_columns as f32 * GLYPH_SIZE * TEXT_SCALE;
+            let action_width = help.iter()
+                .map(|(_, action)| TextRenderer::measure(action, TEXT_SCALE))
+                .fold(0.0, f32::max);
+
+            let width = key_width + action_width + PADDING * 2.0;
+            let height = help.len() as f32 * LINE_HEIGHT + PADDING * 2.0 - 4.0;
+            let x = self.size.width as f32 - MARGIN - width;
+            text.queue_rect(x, MARGIN, width, height, PANEL_COLOR);
+
+            for (i, (key, action)) in help.iter().enumerate() {
+                let y = MARGIN + PADDING + i as f32 * LINE_HEIGHT;
+                text.queue_text(key, x + PADDING, y, TEXT_SCALE, KEY_COLOR);
+                text.queue_text(action, x + PADDING + key_width, y, TEXT_SCALE, TEXT_COLOR);
+            }
+        }
+    }
+
+    /// Nombre de cada cuerpo visible centrado sobre su esfera proyectada. El
+    /// seleccionado se resalta y siempre lleva etiqueta aunque estén ocultas.
+    fn queue_labels(&mut self) {
+        let view_proj = self.camera.build_view_projection_matrix();
+        let (width, height) = (self.size.width as f32, self.size.height as f32);
+        // Píxeles por unidad de mundo a profundidad 1
+        let pixels_per_unit = height * 0.5 / (self.camera.fovy * 0.5).tan();
+
+        for (i, planet) in self.planets.iter().enumerate() {
+            let selected = self.selected == Some(i);
+            if !(self.hud.labels || selected) {
+                continue;
+            }
+            let Some((center, depth)) = picking::project(view_proj, planet.get_position(), width, height) else {
+                continue;
+            };
+
+            let screen_radius = planet.radius() * pixels_per_unit / depth;
+            let label_width = TextRenderer::measure(&planet.name, TEXT_SCALE);
+            let x = center.x - label_width * 0.5;
+            let y = center.y - screen_radius - LINE_HEIGHT;
+            if x + label_width < 0.0 || x > width || y + LINE_HEIGHT < 0.0 || y > height {
+                continue;
+            }
+
+            let color = if selected { SELECTED_COLOR } else { LABEL_COLOR };
+            if selected {
+                self.state.text.queue_rect(x - 4.0, y - 4.0, label_width + 8.0, LINE_HEIGHT + 2.0, PANEL_COLOR);
+            }
+            self.state.text.queue_text(&planet.name, x, y, TEXT_SCALE, color);
+        }
+    }
+
+    /// Mira en el centro de la pantalla para seleccionar con el cursor capturado.
+    fn queue_crosshair(&mut self) {
+        let (cx, cy) = (self.size.width as f32 * 0.5, self.size.height as f32 * 0.5);
+        let text = &mut self.state.text;
+        text.queue_rect(cx - 8.0, cy - 1.0, 6.0, 2.0, TEXT_COLOR);
+        text.queue_rect(cx + 2.0, cy - 1.0, 6.0, 2.0, TEXT_COLOR);
+        text.queue_rect(cx - 1.0, cy - 8.0, 2.0, 6.0, TEXT_COLOR);
+        text.queue_rect(cx - 1.0, cy + 2.0, 2.0, 6.0, TEXT_COLOR);
+    }
+
+    /// Ficha del cuerpo: radio, distancia a la cámara y elementos orbitales.
+    fn body_info(&self, body: usize) -> Vec<String> {
+        let planet = &self.planets[body];
+        let position = planet.get_position();
+        let parent = planet.parent.map(|parent| self.planets[parent].name.as_str());
+
+        let mut lines = vec![
+            format!("{} ({:?})", planet.name, planet.planet_type),
+            format!("Radio {:.2}  Distancia {:.1}", planet.radius(), (position - self.camera.position).length()),
+        ];
+
+        match &planet.orbit {
+            Some(orbit) => {
+                let center = planet.parent.map_or(Vec3::ZERO, |parent| self.planets[parent].get_position());
+                lines.push(format!("Orbita {} a {:.1}", parent.unwrap_or("el origen"), (position - center).length()));
+                lines.push(format!("Semieje {:.2}  Excentricidad {:.3}", orbit.semi_major_axis, orbit.eccentricity));
+                lines.push(format!(
+                    "Inclinación {:.1}°  Nodo {:.1}°  Periapsis {:.1}°",
+                    orbit.inclination.to_degrees(),
+                    orbit.longitude_of_ascending_node.to_degrees(),
+                    orbit.argument_of_periapsis.to_degrees(),
+                ));
+                lines.push(match orbit.period() {
+                    Some(period) => format!("Periodo {:.1} s", period),
+                    None => "Periodo -".to_string(),
+                });
+            }
+            None => lines.push(match parent {
+                Some(parent) => format!("Fijo en {}", parent),
+                None => "Sin órbita".to_string(),
+            }),
+        }
+
+        lines
+    }
+}
+
+/// Escala de tiempo con dos decimales, o más si es pequeña (x0.125, x0.031...).
+fn format_scale(scale: f32) -> String {
+    if scale != 0.0 && scale.abs() < 0.1 {
+        format!("{:.3}", scale)
+    } else {
+        format!("{:.2}", scale)
+    }
+}
+
+/// Fondo semitransparente con una línea de texto por elemento.
+fn queue_panel(text: &mut TextRenderer, x: f32, y: f32, lines: &[String]) {
+    let width = lines.iter().map(|line| TextRenderer::measure(line, TEXT_SCALE)).fold(0.0, f32::max);
+    let height = lines.len() as f32 * LINE_HEIGHT + PADDING * 2.0 - 4.0;
+    text.queue_rect(x, y, width + PADDING * 2.0, height, PANEL_COLOR);
+
+    for (i, line) in lines.iter().enumerate() {
+        text.queue_text(line, x + PADDING, y + PADDING + i as f32 * LINE_HEIGHT, TEXT_SCALE, TEXT_COLOR);
+    }
+}
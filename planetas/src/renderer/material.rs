@@ -0,0 +1,447 @@
+use std::path::{Path, PathBuf};
+
+use bytemuck::{Pod, Zeroable};
+use serde::Deserialize;
+use wgpu::util::DeviceExt;
+
+/// Material de un cuerpo tal como se describe en la escena. Todas las texturas
+/// son opcionales; las que faltan no cambian nada del shader procedural.
+///
+/// Las rutas son relativas al archivo de escena y pueden ser PNG, KTX2 o HDR.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct MaterialSettings {
+    /// Color base (sRGB); su alfa también multiplica `mix`
+    pub albedo: Option<PathBuf>,
+    /// Mapa de normales en espacio tangente (convención OpenGL: verde hacia arriba)
+    pub normal: Option<PathBuf>,
+    /// Luz propia (sRGB), que se suma también en el lado de noche
+    pub emissive: Option<PathBuf>,
+    /// Intensidad del brillo especular (canal rojo)
+    pub specular: Option<PathBuf>,
+    /// Cuánto de la textura de albedo se mezcla con el color procedural (0 a 1)
+    pub mix: f32,
+    pub normal_strength: f32,
+    pub emissive_strength: f32,
+    pub specular_strength: f32,
+    /// Imágenes ya decodificadas por `load_images`, en el orden de los mapas
+    #[serde(skip)]
+    pub images: [Option<TextureData>; 4],
+}
+
+impl Default for MaterialSettings {
+    fn default() -> Self {
+        Self {
+            albedo: None,
+            normal: None,
+            emissive: None,
+            specular: None,
+            mix: 1.0,
+            normal_strength: 1.0,
+            emissive_strength: 1.0,
+            specular_strength: 1.0,
+            images: Default::default(),
+        }
+    }
+}
+
+/// Mapas de un material: nombre del campo en la escena, si está en sRGB y el
+/// texel que se usa cuando falta (no altera el resultado del shader).
+const MAPS: [(&str, bool, [u8; 4]); 4] = [
+    ("albedo", true, [255, 255, 255, 0]),
+    ("normal", false, [128, 128, 255, 255]),
+    ("emissive", true, [0, 0, 0, 255]),
+    ("specular", false, [255, 255, 255, 255]),
+];
+
+impl MaterialSettings {
+    fn paths(&self) -> [Option<&PathBuf>; 4] {
+        [self.albedo.as_ref(), self.normal.as_ref(), self.emissive.as_ref(), self.specular.as_ref()]
+    }
+
+    /// Lee y decodifica las texturas (rutas relativas a `base_dir`). En caso de
+    /// error devuelve el campo que falla y el motivo.
+    pub fn load_images(&mut self, base_dir: &Path) -> Result<(), (&'static str, String)> {
+        let mut images: [Option<TextureData>; 4] = Default::default();
+        for (i, path) in self.paths().into_iter().enumerate() {
+            let (field, srgb, _) = MAPS[i];
+            if let Some(path) = path {
+                let path = base_dir.join(path);
+                let image = TextureData::load(&path, srgb)
+                    .map_err(|message| (field, format!("no se pudo cargar {}: {}", path.display(), message)))?;
+                images[i] = Some(image);
+            }
+        }
+        self.images = images;
+        Ok(())
+    }
+}
+
+/// Textura decodificada en memoria, lista para subir a la GPU.
+pub struct TextureData {
+    pub width: u32,
+    pub height: u32,
+    pub format: wgpu::TextureFormat,
+    /// Niveles de mipmap, del mayor al menor
+    pub levels: Vec<Vec<u8>>,
+}
+
+impl TextureData {
+    pub fn load(path: &Path, srgb: bool) -> Result<Self, String> {
+        let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_ascii_lowercase();
+        let bytes = std::fs::read(path).map_err(|e| e.to_string())?;
+        match extension.as_str() {
+            "png" => Self::from_png(&bytes, srgb),
+            "ktx2" => Self::from_ktx2(&bytes, srgb),
+            "hdr" => Self::from_hdr(&bytes),
+            _ => Err("formato no soportado (usa .png, .ktx2 o .hdr)".to_string()),
+        }
+    }
+
+    /// PNG de cualquier tipo de color, convertido a RGBA8 y con los mipmaps
+    /// generados promediando bloques de 2x2.
+    fn from_png(bytes: &[u8], srgb: bool) -> Result<Self, String> {
+        let mut decoder = png::Decoder::new(bytes);
+        decoder.set_transformations(png::Transformations::normalize_to_color8());
+        let mut reader = decoder.read_info().map_err(|e| e.to_string())?;
+        let mut buffer = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buffer).map_err(|e| e.to_string())?;
+        buffer.truncate(info.buffer_size());
+
+        let rgba: Vec<u8> = match info.color_type {
+            png::ColorType::Rgba => buffer,
+            png::ColorType::Rgb => buffer.chunks_exact(3).flat_map(|p| [p[0], p[1], p[2], 255]).collect(),
+            png::ColorType::GrayscaleAlpha => buffer.chunks_exact(2).flat_map(|p| [p[0], p[0], p[0], p[1]]).collect(),
+            png::ColorType::Grayscale => buffer.iter().flat_map(|&g| [g, g, g, 255]).collect(),
+            png::ColorType::Indexed => return Err("PNG con paleta sin expandir".to_string()),
+        };
+
+        Ok(Self::from_rgba8(rgba, info.width, info.height, srgb))
+    }
+
+    /// Imagen RGBA8 ya decodificada; los mipmaps se generan aquí.
+    pub fn from_rgba8(rgba: Vec<u8>, width: u32, height: u32, srgb: bool) -> Self {
+        Self {
+            width,
+            height,
+            format: rgba8_format(srgb),
+            levels: mip_chain(rgba, width, height),
+        }
+    }
+
+    /// KTX2 sin supercompresión, 2D y de una sola capa. Se usan el formato y los
+    /// mipmaps del archivo; los formatos BC necesitan que la GPU los soporte. Si el
+    /// formato tiene variante sRGB, debe coincidir con `srgb`.
+    fn from_ktx2(bytes: &[u8], srgb: bool) -> Result<Self, String> {
+        let reader = ktx2::Reader::new(bytes).map_err(|e| e.to_string())?;
+        let header = reader.header();
+
+        if header.supercompression_scheme.is_some() {
+            return Err("KTX2 supercomprimido (zstd/Basis) no soportado".to_string());
+        }
+        if header.face_count != 1 || header.layer_count > 1 || header.pixel_depth > 1 {
+            return Err("solo se admiten texturas 2D de una capa".to_string());
+        }
+        let format = header
+            .format
+            .and_then(ktx2_format)
+            .ok_or_else(|| format!("formato {:?} no soportado", header.format))?;
+        // Los formatos sin variante sRGB (flotantes, BC4/BC5...) valen para ambos
+        let has_srgb_variant = format.add_srgb_suffix() != format.remove_srgb_suffix();
+        if has_srgb_variant && format.is_srgb() != srgb {
+            let expected = if srgb { "sRGB" } else { "lineal" };
+            return Err(format!("el formato {:?} no es {}, como requiere este mapa", format, expected));
+        }
+        let (block_width, block_height) = format.block_dimensions();
+        if header.pixel_width % block_width != 0 || header.pixel_height % block_height != 0 {
+            return Err(format!("el tamaño debe ser múltiplo de {}x{}", block_width, block_height));
+        }
+
+        Ok(Self {
+            width: header.pixel_width,
+            height: header.pixel_height.max(1),
+            format,
+            levels: reader.levels().map(|level| level.data.to_vec()).collect(),
+        })
+    }
+
+    /// Radiance HDR (lineal y sin límite de brillo) convertido a RGBA16F, sin mipmaps.
+    fn from_hdr(bytes: &[u8]) -> Result<Self, String> {
+        let image = image::load_from_memory_with_format(bytes, image::ImageFormat::Hdr)
+            .map_err(|e| e.to_string())?
+            .into_rgba32f();
+        let texels = image
+            .as_raw()
+            .iter()
+            .flat_map(|&value| half::f16::from_f32(value).to_le_bytes())
+            .collect();
+
+        Ok(Self {
+            width: image.width(),
+            height: image.height(),
+            format: wgpu::TextureFormat::Rgba16Float,
+            levels: vec![texels],
+        })
+    }
+
+    /// Textura de 1x1 con un solo color.
+    pub fn solid(texel: [u8; 4], srgb: bool) -> Self {
+        Self {
+            width: 1,
+            height: 1,
+            format: rgba8_format(srgb),
+            levels: vec![texel.to_vec()],
+        }
+    }
+
+    /// Comprueba que la GPU pueda crear la textura: formato soportado y tamaño
+    /// dentro de `max_texture_dimension_2d` (wgpu aborta si no).
+    pub fn check_device(&self, device: &wgpu::Device) -> Result<(), String> {
+        if !device.features().contains(self.format.required_features()) {
+            return Err(format!("la GPU no soporta {:?}", self.format));
+        }
+        let max = device.limits().max_texture_dimension_2d;
+        if self.width > max || self.height > max {
+            return Err(format!("{}x{} supera el tamaño máximo de la GPU ({})", self.width, self.height, max));
+        }
+        Ok(())
+    }
+
+    pub fn upload(&self, device: &wgpu::Device, queue: &wgpu::Queue, label: &str) -> wgpu::Texture {
+        device.create_texture_with_data(
+            queue,
+            &wgpu::TextureDescriptor {
+                label: Some(label),
+                size: wgpu::Extent3d {
+                    width: self.width,
+                    height: self.height,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: self.levels.len() as u32,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: self.format,
+                usage: wgpu::TextureUsages::TEXTURE_BINDING,
+                view_formats: &[],
+            },
+            wgpu::util::TextureDataOrder::MipMajor,
+            &self.levels.concat(),
+        )
+    }
+}
+
+fn rgba8_format(srgb: bool) -> wgpu::TextureFormat {
+    if srgb {
+        wgpu::TextureFormat::Rgba8UnormSrgb
+    } else {
+        wgpu::TextureFormat::Rgba8Unorm
+    }
+}
+
+fn ktx2_format(format: ktx2::Format) -> Option<wgpu::TextureFormat> {
+    use wgpu::TextureFormat as F;
+    Some(match format {
+        ktx2::Format::R8G8B8A8_UNORM => F::Rgba8Unorm,
+        ktx2::Format::R8G8B8A8_SRGB => F::Rgba8UnormSrgb,
+        ktx2::Format::BC1_RGBA_UNORM_BLOCK => F::Bc1RgbaUnorm,
+        ktx2::Format::BC1_RGBA_SRGB_BLOCK => F::Bc1RgbaUnormSrgb,
+        ktx2::Format::BC3_UNORM_BLOCK => F::Bc3RgbaUnorm,
+        ktx2::Format::BC3_SRGB_BLOCK => F::Bc3RgbaUnormSrgb,
+        ktx2::Format::BC5_UNORM_BLOCK => F::Bc5RgUnorm,
+        ktx2::Format::BC7_UNORM_BLOCK => F::Bc7RgbaUnorm,
+        ktx2::Format::BC7_SRGB_BLOCK => F::Bc7RgbaUnormSrgb,
+        _ => return None,
+    })
+}
+
+/// Niveles de mipmap de una imagen RGBA8 hasta 1x1 (filtro de caja de 2x2).
+fn mip_chain(base: Vec<u8>, width: u32, height: u32) -> Vec<Vec<u8>> {
+    let mut levels = vec![base];
+    let (mut width, mut height) = (width, height);
+
+    while width > 1 || height > 1 {
+        let previous = levels.last().unwrap();
+        let (next_width, next_height) = ((width / 2).max(1), (height / 2).max(1));
+        let mut level = Vec::with_capacity((next_width * next_height * 4) as usize);
+
+        for y in 0..next_height {
+            for x in 0..next_width {
+                for channel in 0..4 {
+                    let mut sum = 0u32;
+                    for (dx, dy) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+                        let sx = (x * 2 + dx).min(width - 1);
+                        let sy = (y * 2 + dy).min(height - 1);
+                        sum += previous[((sy * width + sx) * 4 + channel) as usize] as u32;
+                    }
+                    level.push(((sum + 2) / 4) as u8);
+                }
+            }
+        }
+
+        levels.push(level);
+        width = next_width;
+        height = next_height;
+    }
+
+    levels
+}
+
+/// Parámetros del material en el shader (grupo 2, binding 0).
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Pod, Zeroable)]
+struct MaterialUniforms {
+    mix: f32,
+    normal_strength: f32,
+    emissive_strength: f32,
+    specular_strength: f32,
+}
+
+/// Material ya subido a la GPU: uniform, 4 texturas y sampler (grupo 2 de los
+/// pipelines de planetas).
+pub struct Material {
+    pub bind_group: wgpu::BindGroup,
+}
+
+impl Material {
+    pub fn create_bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
+        let texture = |binding| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Texture {
+                sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                view_dimension: wgpu::TextureViewDimension::D2,
+                multisampled: false,
+            },
+            count: None,
+        };
+
+        device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("material_bind_group_layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                texture(1),
+                texture(2),
+                texture(3),
+                texture(4),
+                wgpu::BindGroupLayoutEntry {
+                    binding: 5,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        })
+    }
+
+    /// Sampler compartido: repite en U (la vuelta al planeta) y se ajusta al borde en V.
+    pub fn create_sampler(device: &wgpu::Device) -> wgpu::Sampler {
+        device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Material Sampler"),
+            address_mode_u: wgpu::AddressMode::Repeat,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Linear,
+            anisotropy_clamp: 16,
+            ..Default::default()
+        })
+    }
+
+    /// Sube el material de un cuerpo; sin `settings` queda un material neutro
+    /// que deja el shader procedural tal cual.
+    pub fn new(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        layout: &wgpu::BindGroupLayout,
+        sampler: &wgpu::Sampler,
+        name: &str,
+        settings: Option<&MaterialSettings>,
+    ) -> Self {
+        let images = settings.map(|settings| &settings.images);
+        let textures: Vec<wgpu::Texture> = MAPS
+            .iter()
+            .enumerate()
+            .map(|(i, &(field, srgb, texel))| {
+                let label = format!("{} {}", name, field);
+                match images.and_then(|images| images[i].as_ref()) {
+                    Some(image) => match image.check_device(device) {
+                        Ok(()) => image.upload(device, queue, &label),
+                        Err(message) => {
+                            println!("[material] {}.material.{}: {}, se ignora la textura", name, field, message);
+                            TextureData::solid(texel, srgb).upload(device, queue, &label)
+                        }
+                    },
+                    None => TextureData::solid(texel, srgb).upload(device, queue, &label),
+                }
+            })
+            .collect();
+        let views: Vec<wgpu::TextureView> = textures
+            .iter()
+            .map(|texture| texture.create_view(&wgpu::TextureViewDescriptor::default()))
+            .collect();
+
+        // Sin mapa de normales la intensidad queda a 0 para que el texel neutro
+        // (que no es exactamente (0, 0, 1) en 8 bits) no incline la normal
+        let uniforms = match settings {
+            Some(settings) => MaterialUniforms {
+                mix: settings.mix,
+                normal_strength: if settings.images[1].is_some() { settings.normal_strength } else { 0.0 },
+                emissive_strength: settings.emissive_strength,
+                specular_strength: settings.specular_strength,
+            },
+            None => MaterialUniforms {
+                mix: 0.0,
+                normal_strength: 0.0,
+                emissive_strength: 0.0,
+                specular_strength: 1.0,
+            },
+        };
+        let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Material Uniform Buffer"),
+            contents: bytemuck::cast_slice(&[uniforms]),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("material_bind_group"),
+            layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: uniform_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(&views[0]),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::TextureView(&views[1]),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: wgpu::BindingResource::TextureView(&views[2]),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 4,
+                    resource: wgpu::BindingResource::TextureView(&views[3]),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 5,
+                    resource: wgpu::BindingResource::Sampler(sampler),
+                },
+            ],
+        });
+
+        Self { bind_group }
+    }
+}
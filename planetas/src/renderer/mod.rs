@@ -4,32 +4,92 @@ mod geometry;
 mod planets;
 mod ship;
 mod skybox;
+mod scene;
+mod orbit;
+mod shadows;
+mod postprocess;
+mod text;
+mod hud;
+mod picking;
+mod clock;
+mod recording;
+mod camera_path;
+mod gravity;
+mod collision;
+mod material;
+mod model;
+mod hot_reload;
 
 pub use types::Uniforms;
-use types::RendererState;
+pub use scene::Scene;
+pub use postprocess::PostSettings;
+pub use clock::{TimeSettings, FIXED_STEP};
+pub use recording::{InputEvent, Recording, RecordingError};
+pub use camera_path::{CameraPath, CameraPathError};
+use scene::LightSettings;
+use types::{LightUniforms, RendererState};
 
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 use winit::keyboard::KeyCode;
-use glam::{Mat4, Vec3, Quat};
+use winit::event::MouseButton;
+use winit::window::CursorGrabMode;
+use glam::{Mat4, Vec2, Vec3, Quat};
 
-use crate::camera::Camera;
-use planets::get_warp_points;
-use planets::Planet;
+use crate::camera::{look_rotation, Camera, CameraPose, OrbitCamera};
+use crate::flight::{Ship, ShipControls, ShipView, CHASE_OFFSET};
+use planets::{Planet, PlanetType, WarpPoint};
+use hud::Hud;
+use clock::{FixedTimestep, SimClock};
+use recording::Replay;
+use camera_path::Easing;
+use gravity::NBody;
+use hot_reload::ShaderWatcher;
+use collision::{CollisionEvent, CollisionSettings, Surface};
+use model::Prop;
 
 pub struct Renderer {
-    pub window: Arc<winit::window::Window>,
+    pub window: Option<Arc<winit::window::Window>>,
     state: RendererState,
     pub size: winit::dpi::PhysicalSize<u32>,
     camera: Camera,
-    time: f32,
+    /// Tiempo orbital: posiciones de los cuerpos
+    clock: SimClock,
+    /// Tiempo propio de la animación de los shaders; None si sigue al orbital
+    shader_clock: Option<SimClock>,
+    /// Tiempo real aún no simulado, repartido en pasos de `FIXED_STEP`
+    timestep: FixedTimestep,
+    /// Pose de la cámara al empezar el último paso
+    previous_pose: CameraPose,
+    /// Pasos fijos simulados desde el inicio
+    step_count: u64,
+    ship: Ship,
+    /// Pose de la nave al empezar el último paso
+    previous_ship_pose: CameraPose,
+    /// Vista de la cámara pilotando la nave; None para la cámara libre
+    ship_view: Option<ShipView>,
+    boost: bool,
+    /// Grabación en curso y archivo donde se guardará
+    recording: Option<(PathBuf, Recording)>,
+    replay: Option<Replay>,
     planets: Vec<Planet>,
-    warp_points: Vec<planets::WarpPoint>,
-    current_warp: usize,
+    /// Integración N-cuerpos en el modo gravedad; None sigue las órbitas analíticas
+    gravity: Option<NBody>,
+    warp_points: Vec<WarpPoint>,
+    light: LightSettings,
+    collision: CollisionSettings,
+    /// `Scene::hash` de la escena cargada, para las grabaciones
+    scene_hash: u64,
+    /// Giro y escala del modelo de la nave de la escena, si tiene
+    ship_model: Option<Mat4>,
+    props: Vec<Prop>,
+    post: PostSettings,
+    current_warp: Option<WarpPoint>,
     warp_progress: f32,
     is_warping: bool,
     warp_start_pos: Vec3,
-    warp_start_yaw: f32,
-    warp_start_pitch: f32,
+    warp_start_orientation: Quat,
     move_forward: bool,
     move_backward: bool,
     move_left: bool,
@@ -40,33 +100,86 @@ pub struct Renderer {
     rotate_right: bool,
     rotate_up: bool,
     rotate_down: bool,
+    roll_left: bool,
+    roll_right: bool,
+    zoom_modifier: bool,
+    mouse_look: bool,
+    pan_drag: bool,
+    /// Movimiento del ratón acumulado hasta el próximo paso
+    pending_mouse: Vec2,
+    orbit: Option<OrbitCamera>,
+    camera_path: Option<CameraPath>,
+    /// Segundos recorridos del camino; None si no se está reproduciendo
+    path_time: Option<f32>,
+    cursor: Vec2,
+    selected: Option<usize>,
+    hud: Hud,
+    /// Recarga en caliente de los shaders; None fuera del modo de desarrollo
+    shader_watcher: Option<ShaderWatcher>,
 }
 
 impl Renderer {
-    pub async fn new(window: winit::window::Window) -> Self {
+    pub async fn new(window: winit::window::Window, scene: Scene, post: PostSettings) -> Self {
         let window = Arc::new(window);
         let size = window.inner_size();
         
-        let state = RendererState::new(window.clone(), size).await;
+        let state = RendererState::new(window.clone(), size, &scene).await;
+        Self::with_state(Some(window), state, size, scene, post)
+    }
+
+    /// Renderer sin ventana que dibuja a una textura offscreen (ver `capture_frame`).
+    pub async fn new_headless(width: u32, height: u32, scene: Scene, post: PostSettings) -> Self {
+        let size = winit::dpi::PhysicalSize::new(width, height);
+        let state = RendererState::new_headless(size, &scene).await;
+        Self::with_state(None, state, size, scene, post)
+    }
+
+    fn with_state(
+        window: Option<Arc<winit::window::Window>>,
+        state: RendererState,
+        size: winit::dpi::PhysicalSize<u32>,
+        scene: Scene,
+        post: PostSettings,
+    ) -> Self {
         let camera = Camera::new(size.width, size.height);
+        let previous_pose = camera.pose();
+        // La nave empieza delante de la cámara, justo donde la deja la vista en tercera persona
+        let ship = Ship::new(camera.position - camera.orientation * CHASE_OFFSET, camera.orientation);
+        let previous_ship_pose = ship.pose();
         
-        let planets = planets::create_planet_system();
-        let warp_points = get_warp_points();
+        let Scene { bodies: planets, warp_points, light, collision, ship_model, props, skybox: _, hash: scene_hash } = scene;
+        let ship_model = ship_model.map(|model| model.local_matrix());
 
         Self {
             window,
             state,
             size,
             camera,
-            time: 0.0,
+            clock: SimClock::new(0.0, 1.0),
+            shader_clock: None,
+            timestep: FixedTimestep::new(),
+            previous_pose,
+            step_count: 0,
+            ship,
+            previous_ship_pose,
+            ship_view: Some(ShipView::Chase),
+            boost: false,
+            recording: None,
+            replay: None,
             planets,
+            gravity: None,
             warp_points,
-            current_warp: 0,
+            light,
+            collision,
+            scene_hash,
+            ship_model,
+            props,
+            post,
+            current_warp: None,
             warp_progress: 0.0,
             is_warping: false,
             warp_start_pos: Vec3::ZERO,
-            warp_start_yaw: 0.0,
-            warp_start_pitch: 0.0,
+            warp_start_orientation: Quat::IDENTITY,
             move_forward: false,
             move_backward: false,
             move_left: false,
@@ -77,6 +190,25 @@ impl Renderer {
             rotate_right: false,
             rotate_up: false,
             rotate_down: false,
+            roll_left: false,
+            roll_right: false,
+            zoom_modifier: false,
+            mouse_look: false,
+            pan_drag: false,
+            pending_mouse: Vec2::ZERO,
+            orbit: None,
+            camera_path: None,
+            path_time: None,
+            cursor: Vec2::ZERO,
+            selected: None,
+            hud: Hud::new(),
+            shader_watcher: None,
+        }
+    }
+
+    pub fn request_redraw(&self) {
+        if let Some(window) = &self.window {
+            window.request_redraw();
         }
     }
 
@@ -88,7 +220,74 @@ impl Renderer {
         }
     }
 
-    pub fn input(&mut self, key: KeyCode, pressed: bool) {
+    /// Punto de entrada de todas las entradas del usuario. Se graban si hay una
+    /// grabación activa y se ignoran mientras se reproduce una sesión.
+    pub fn handle_input(&mut self, event: InputEvent) {
+        if self.replay.is_some() {
+            return;
+        }
+        if let Some((_, recording)) = &mut self.recording {
+            recording.events.push((self.step_count, event));
+        }
+        self.apply_input(event);
+    }
+
+    fn apply_input(&mut self, event: InputEvent) {
+        match event {
+            InputEvent::Key { key, pressed } => self.input(key, pressed),
+            InputEvent::MouseButton { button, pressed } => self.mouse_button(button, pressed),
+            InputEvent::CursorMoved { x, y } => self.cursor = Vec2::new(x, y),
+            InputEvent::MouseMotion { dx, dy } => self.mouse_motion(dx, dy),
+            InputEvent::Scroll { steps } => self.scroll(steps),
+            InputEvent::MouseLook { enabled } => self.set_mouse_look(enabled),
+        }
+    }
+
+    /// Empieza a grabar las entradas; se guardan en `path` con `stop_recording`.
+    /// Debe llamarse antes del primer `update` para que la grabación parta del
+    /// estado inicial.
+    pub fn start_recording(&mut self, path: PathBuf) {
+        let time = TimeSettings {
+            start: self.clock.time,
+            scale: self.clock.scale,
+            shader_scale: self.shader_clock.as_ref().map(|clock| clock.scale),
+        };
+        println!("[grabacion] grabando en {}", path.display());
+        let mut recording = Recording::new(self.size.width, self.size.height, time, self.scene_hash);
+        recording.gravity = self.gravity.is_some();
+        self.recording = Some((path, recording));
+    }
+
+    /// Termina la grabación y la guarda. Devuelve la ruta si había una en curso.
+    pub fn stop_recording(&mut self) -> Result<Option<PathBuf>, RecordingError> {
+        let Some((path, mut recording)) = self.recording.take() else {
+            return Ok(None);
+        };
+        recording.steps = self.step_count;
+        recording.save(&path)?;
+        println!("[grabacion] {} pasos y {} eventos guardados en {}", recording.steps, recording.events.len(), path.display());
+        Ok(Some(path))
+    }
+
+    /// Reproduce una sesión grabada desde su estado inicial. Mientras dura se
+    /// ignoran las entradas del usuario.
+    pub fn start_replay(&mut self, recording: Recording) {
+        if (recording.width, recording.height) != (self.size.width, self.size.height) {
+            eprintln!(
+                "[replay] grabado a {}x{} y reproducido a {}x{}: los clics pueden no coincidir",
+                recording.width, recording.height, self.size.width, self.size.height
+            );
+        }
+        if recording.scene.is_some_and(|hash| hash != self.scene_hash) {
+            eprintln!("[replay] grabado con otra escena (o con otra versión de ella): el recorrido no será el mismo");
+        }
+        self.set_time_settings(recording.time);
+        self.set_gravity(recording.gravity);
+        println!("[replay] {} pasos, {} eventos", recording.steps, recording.events.len());
+        self.replay = Some(Replay::new(recording));
+    }
+
+    fn input(&mut self, key: KeyCode, pressed: bool) {
         match key {
             KeyCode::KeyW => self.move_forward = pressed,
             KeyCode::KeyS => self.move_backward = pressed,
@@ -100,6 +299,20 @@ impl Renderer {
             KeyCode::ArrowRight => self.rotate_right = pressed,
             KeyCode::ArrowUp => self.rotate_up = pressed,
             KeyCode::ArrowDown => self.rotate_down = pressed,
+            KeyCode::KeyQ => self.roll_left = pressed,
+            KeyCode::KeyE => self.roll_right = pressed,
+            KeyCode::ControlLeft | KeyCode::ControlRight => self.zoom_modifier = pressed,
+            KeyCode::Tab => self.boost = pressed,
+            KeyCode::F2 if pressed => self.cycle_ship_view(),
+            KeyCode::Escape if pressed => self.set_mouse_look(!self.mouse_look),
+            KeyCode::KeyF if pressed => self.toggle_orbit(),
+            KeyCode::KeyG if pressed => self.warp_to_selected(),
+            KeyCode::KeyL if pressed => self.hud.labels = !self.hud.labels,
+            KeyCode::Backspace if pressed => self.select(None),
+            KeyCode::KeyH if pressed => self.hud.show_help = !self.hud.show_help,
+            KeyCode::F1 if pressed => self.hud.visible = !self.hud.visible,
+            KeyCode::BracketLeft if pressed => self.cycle_orbit_target(-1),
+            KeyCode::BracketRight if pressed => self.cycle_orbit_target(1),
             KeyCode::Digit1 if pressed => self.initiate_warp(0),
             KeyCode::Digit2 if pressed => self.initiate_warp(1),
             KeyCode::Digit3 if pressed => self.initiate_warp(2),
@@ -107,70 +320,561 @@ impl Renderer {
             KeyCode::Digit5 if pressed => self.initiate_warp(4),
             KeyCode::Digit6 if pressed => self.initiate_warp(5),
             KeyCode::Digit7 if pressed => self.initiate_warp(6),
+            KeyCode::Digit8 if pressed => self.initiate_warp(7),
+            KeyCode::Digit9 if pressed => self.initiate_warp(8),
+            KeyCode::KeyT if pressed => {
+                self.post.tonemapper = self.post.tonemapper.next();
+                println!("[post] tonemapper {}", self.post.tonemapper.name());
+            }
+            KeyCode::KeyB if pressed => {
+                self.post.bloom = !self.post.bloom;
+                println!("[post] bloom {}", if self.post.bloom { "activado" } else { "desactivado" });
+            }
+            KeyCode::Minus if pressed => self.change_exposure(-0.25),
+            KeyCode::Equal if pressed => self.change_exposure(0.25),
+            KeyCode::KeyP if pressed => self.set_paused(!self.clock.paused),
+            KeyCode::KeyN if pressed => self.step_time(),
+            KeyCode::KeyR if pressed => self.set_time_scale(-self.clock.scale),
+            KeyCode::PageUp if pressed => self.set_time_scale(self.clock.scale * 2.0),
+            KeyCode::PageDown if pressed => self.set_time_scale(self.clock.scale * 0.5),
+            KeyCode::Home if pressed => self.set_time(0.0),
+            KeyCode::KeyC if pressed => self.toggle_camera_path(),
+            KeyCode::KeyV if pressed => {
+                if let Some(camera_path) = &mut self.camera_path {
+                    camera_path.looped = !camera_path.looped;
+                    println!("[camino] bucle {}", if camera_path.looped { "activado" } else { "desactivado" });
+                }
+            }
+            KeyCode::KeyM if pressed => self.set_gravity(self.gravity.is_none()),
+            KeyCode::KeyK if pressed => {
+                let scale = if self.shader_clock.is_some() { None } else { Some(1.0) };
+                self.set_shader_time_scale(scale);
+            }
             _ => {}
         }
     }
 
+    /// Aplica el estado inicial de los relojes.
+    pub fn set_time_settings(&mut self, settings: TimeSettings) {
+        self.clock = SimClock::new(settings.start, 1.0);
+        self.clock.set_scale(settings.scale);
+        self.set_shader_time_scale(settings.shader_scale);
+        self.restart_gravity();
+    }
+
+    /// Salta al instante `time` (en segundos) del tiempo orbital. En el modo
+    /// gravedad la integración vuelve a empezar desde las órbitas de ese instante.
+    pub fn set_time(&mut self, time: f32) {
+        self.clock.set_time(time);
+        self.restart_gravity();
+        println!("[tiempo] t = {:.2} s", time);
+    }
+
+    /// Modo de desarrollo: usa los shaders de `dir` en vez de las copias
+    /// embebidas y recompila los pipelines afectados cada vez que se guarda uno.
+    pub fn watch_shaders(&mut self, dir: PathBuf) {
+        match ShaderWatcher::new(dir.clone()) {
+            Ok(watcher) => {
+                self.state.reload_all_shaders(&dir);
+                println!("[shaders] recarga en caliente desde {}", dir.display());
+                self.shader_watcher = Some(watcher);
+            }
+            Err(err) => println!("[shaders] no se puede vigilar {}: {}", dir.display(), err),
+        }
+    }
+
+    /// Activa el modo gravedad (N-cuerpos, partiendo de las órbitas actuales) o
+    /// vuelve a las órbitas analíticas en el instante actual.
+    pub fn set_gravity(&mut self, enabled: bool) {
+        if enabled == self.gravity.is_some() {
+            return;
+        }
+        if enabled {
+            self.gravity = Some(NBody::from_orbits(&mut self.planets, self.clock.time));
+            println!("[gravedad] N-cuerpos");
+        } else {
+            self.gravity = None;
+            planets::update_system(&mut self.planets, self.clock.time);
+            println!("[gravedad] órbitas analíticas");
+        }
+    }
+
+    fn restart_gravity(&mut self) {
+        if self.gravity.is_some() {
+            self.gravity = Some(NBody::from_orbits(&mut self.planets, self.clock.time));
+        }
+    }
+
+    /// Escala del tiempo orbital; negativa hace retroceder las órbitas.
+    pub fn set_time_scale(&mut self, scale: f32) {
+        self.clock.set_scale(scale);
+        println!("[tiempo] escala x{:.3}", self.clock.scale);
+    }
+
+    pub fn set_paused(&mut self, paused: bool) {
+        self.clock.set_paused(paused);
+        println!("[tiempo] {}", if paused { "pausa" } else { "reanudado" });
+    }
+
+    /// En pausa, avanza el tiempo orbital un solo frame.
+    pub fn step_time(&mut self) {
+        self.clock.step();
+    }
+
+    /// Separa la animación de los shaders del tiempo orbital con su propia
+    /// escala (por ejemplo, para seguir animando con las órbitas en pausa), o la
+    /// vuelve a enlazar con None.
+    pub fn set_shader_time_scale(&mut self, scale: Option<f32>) {
+        match scale {
+            Some(scale) => {
+                let mut clock = SimClock::new(self.shader_time(1.0), 1.0);
+                clock.set_scale(scale);
+                println!("[tiempo] shaders independientes x{:.3}", clock.scale);
+                self.shader_clock = Some(clock);
+            }
+            None => {
+                if self.shader_clock.take().is_some() {
+                    println!("[tiempo] shaders enlazados al tiempo orbital");
+                }
+            }
+        }
+    }
+
+    /// Tiempo que reciben los shaders en `Uniforms::time`, interpolado entre el
+    /// paso anterior y el actual.
+    fn shader_time(&self, alpha: f32) -> f32 {
+        self.shader_clock.as_ref().unwrap_or(&self.clock).interpolated(alpha)
+    }
+
+    /// Carga el camino de cámara que se reproduce con C, resolviendo sus cuerpos
+    /// en la escena actual.
+    pub fn set_camera_path(&mut self, mut camera_path: CameraPath) -> Result<(), CameraPathError> {
+        camera_path.resolve_bodies(&self.planets)?;
+        println!(
+            "[camino] {}: {} claves, {:.1} s",
+            camera_path.name,
+            camera_path.keyframes.len(),
+            camera_path.duration()
+        );
+        self.camera_path = Some(camera_path);
+        self.path_time = None;
+        Ok(())
+    }
+
+    /// Empieza a reproducir el camino cargado desde el principio, o lo detiene
+    /// dejando la cámara donde esté.
+    pub fn toggle_camera_path(&mut self) {
+        let Some(camera_path) = &self.camera_path else {
+            println!("[camino] no hay ningún camino cargado (--camera-path)");
+            return;
+        };
+
+        if self.path_time.take().is_some() {
+            println!("[camino] detenido");
+        } else {
+            println!("[camino] reproduciendo {}", camera_path.name);
+            self.path_time = Some(camera_path.keyframes[0].time);
+            self.orbit = None;
+            self.is_warping = false;
+        }
+    }
+
+    /// Muestra u oculta todo el HUD (también con F1).
+    pub fn set_hud_visible(&mut self, visible: bool) {
+        self.hud.visible = visible;
+    }
+
+    /// Activa o desactiva mirar con el ratón y captura o libera el cursor. Sin
+    /// ventana (o durante una reproducción) solo cambia el modo, sin tocar el cursor.
+    ///
+    /// El modo cambia aunque no se pueda capturar el cursor: así depende solo de
+    /// las entradas y una grabación se reproduce igual en cualquier plataforma.
+    fn set_mouse_look(&mut self, enabled: bool) {
+        if enabled == self.mouse_look {
+            return;
+        }
+        self.mouse_look = enabled;
+        let Some(window) = self.window.as_ref().filter(|_| self.replay.is_none()) else {
+            return;
+        };
+
+        if enabled {
+            // No todas las plataformas soportan Locked; Confined es la alternativa
+            let grabbed = window.set_cursor_grab(CursorGrabMode::Locked)
+                .or_else(|_| window.set_cursor_grab(CursorGrabMode::Confined));
+            if let Err(err) = grabbed {
+                // El movimiento relativo del ratón sigue llegando; solo queda el cursor a la vista
+                eprintln!("[camara] no se pudo capturar el cursor: {}", err);
+                return;
+            }
+        } else {
+            let _ = window.set_cursor_grab(CursorGrabMode::None);
+        }
+        window.set_cursor_visible(!enabled);
+    }
+
+    /// Clic izquierdo selecciona el cuerpo bajo el cursor (o bajo la mira si el
+    /// cursor está capturado); en el vacío captura el cursor. Con el derecho
+    /// pulsado el ratón desplaza el punto de mira de la cámara orbital.
+    fn mouse_button(&mut self, button: MouseButton, pressed: bool) {
+        match button {
+            MouseButton::Left if pressed && self.mouse_look => {
+                let center = Vec2::new(self.size.width as f32, self.size.height as f32) * 0.5;
+                self.select(self.pick(center));
+            }
+            MouseButton::Left if pressed => match self.pick(self.cursor) {
+                Some(body) => self.select(Some(body)),
+                None => self.set_mouse_look(true),
+            },
+            MouseButton::Right => self.pan_drag = pressed,
+            _ => {}
+        }
+    }
+
+    /// Movimiento relativo del ratón (en píxeles) mientras el cursor está
+    /// capturado. Se acumula y se aplica en el siguiente paso de la simulación.
+    fn mouse_motion(&mut self, delta_x: f32, delta_y: f32) {
+        if self.mouse_look {
+            self.pending_mouse += Vec2::new(delta_x, delta_y);
+        }
+    }
+
+    fn apply_mouse_motion(&mut self) {
+        let delta = std::mem::take(&mut self.pending_mouse);
+        if delta == Vec2::ZERO || self.is_warping {
+            return;
+        }
+
+        let (delta_x, delta_y) = (delta.x, delta.y);
+        match &mut self.orbit {
+            Some(orbit) if self.pan_drag => orbit.pan(&self.camera, -delta_x * 0.002, delta_y * 0.002),
+            Some(orbit) => orbit.rotate(delta_x.to_radians(), delta_y.to_radians(), self.camera.sensitivity),
+            None if self.ship_view.is_some() => self.ship.look(delta_x, delta_y, self.camera.sensitivity),
+            None => self.camera.look(delta_x, delta_y),
+        }
+    }
+
+    /// Rueda del ratón: cambia la velocidad, o el campo de visión con Ctrl pulsado.
+    /// En modo órbita acerca o aleja la cámara del cuerpo.
+    fn scroll(&mut self, steps: f32) {
+        if let Some(orbit) = &mut self.orbit {
+            orbit.zoom(steps);
+        } else if self.zoom_modifier {
+            self.camera.zoom(steps);
+            println!("[camara] fov {:.0}°", self.camera.fovy.to_degrees());
+        } else {
+            self.camera.change_speed(steps);
+            println!("[camara] velocidad {:.1}", self.camera.speed);
+        }
+    }
+
+    /// Cuerpo bajo el píxel `pixel`, si lo hay.
+    fn pick(&self, pixel: Vec2) -> Option<usize> {
+        let (origin, dir) = picking::screen_ray(
+            self.camera.build_view_projection_matrix(),
+            pixel.x,
+            pixel.y,
+            self.size.width as f32,
+            self.size.height as f32,
+        );
+        picking::pick_body(&self.planets, origin, dir)
+    }
+
+    fn select(&mut self, body: Option<usize>) {
+        if body != self.selected {
+            match body {
+                Some(body) => println!("[seleccion] {}", self.planets[body].name),
+                None => println!("[seleccion] ninguna"),
+            }
+        }
+        self.selected = body;
+    }
+
+    /// Entra en modo órbita alrededor del cuerpo seleccionado (o, sin selección,
+    /// del más cercano al centro de la pantalla), o vuelve al vuelo libre.
+    fn toggle_orbit(&mut self) {
+        let current = self.orbit.take().map(|orbit| orbit.body);
+        if let Some(body) = self.selected.filter(|&body| Some(body) != current) {
+            self.orbit_body(body);
+            return;
+        }
+        if current.is_some() {
+            println!("[camara] vuelo libre");
+            return;
+        }
+
+        let forward = self.camera.get_forward();
+        let target = self.planets.iter()
+            .enumerate()
+            .map(|(i, planet)| (i, (planet.get_position() - self.camera.position).normalize_or_zero().dot(forward)))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(i, _)| i);
+
+        if let Some(body) = target {
+            self.orbit_body(body);
+        }
+    }
+
+    /// Con la cámara orbital activa, pasa al cuerpo anterior o siguiente de la escena.
+    fn cycle_orbit_target(&mut self, step: isize) {
+        if let Some(orbit) = &self.orbit {
+            let count = self.planets.len() as isize;
+            let body = (orbit.body as isize + step).rem_euclid(count) as usize;
+            self.orbit_body(body);
+        }
+    }
+
+    fn orbit_body(&mut self, body: usize) {
+        let planet = &self.planets[body];
+        println!("[camara] orbitando {}", planet.name);
+        self.is_warping = false;
+        self.path_time = None;
+        self.orbit = Some(OrbitCamera::new(body, planet.get_position(), planet.radius(), &self.camera));
+    }
+
+    /// Pasa de la tercera persona a la cabina y de ahí a la cámara libre, que
+    /// deja la nave donde esté. Volver a pilotar devuelve la cámara a la nave.
+    fn cycle_ship_view(&mut self) {
+        self.ship_view = match self.ship_view {
+            Some(ShipView::Chase) => Some(ShipView::Cockpit),
+            Some(ShipView::Cockpit) => None,
+            None => Some(ShipView::Chase),
+        };
+        match self.ship_view {
+            Some(ShipView::Chase) => println!("[nave] tercera persona"),
+            Some(ShipView::Cockpit) => println!("[nave] cabina"),
+            None => println!("[nave] cámara libre"),
+        }
+    }
+
+    /// Lo que mueven las teclas y el warp: la nave si se está pilotando, si no la cámara.
+    fn controlled_pose(&self) -> CameraPose {
+        match self.ship_view {
+            Some(_) => self.ship.pose(),
+            None => self.camera.pose(),
+        }
+    }
+
+    fn set_controlled_pose(&mut self, pose: CameraPose) {
+        match self.ship_view {
+            Some(_) => self.ship.set_pose(pose),
+            None => self.camera.set_pose(pose),
+        }
+    }
+
+    /// Cambia la exposición en pasos de `stops` (potencias de 2).
+    fn change_exposure(&mut self, stops: f32) {
+        self.post.exposure = (self.post.exposure * stops.exp2()).clamp(1.0 / 64.0, 64.0);
+        println!("[post] exposición {:.2}", self.post.exposure);
+    }
+
     fn initiate_warp(&mut self, warp_index: usize) {
-        if warp_index < self.warp_points.len() && !self.is_warping {
-            self.current_warp = warp_index;
+        if let Some(warp) = self.warp_points.get(warp_index) {
+            self.start_warp(warp.clone());
+        }
+    }
+
+    /// Warp hasta el cuerpo seleccionado, llegando desde el lado de la cámara a
+    /// una distancia proporcional a su radio.
+    fn warp_to_selected(&mut self) {
+        let Some(body) = self.selected else {
+            return;
+        };
+        let planet = &self.planets[body];
+        let from_body = (self.controlled_pose().position - planet.get_position()).normalize_or_zero();
+        let dir = (from_body + Vec3::Y * 0.3).try_normalize().unwrap_or(Vec3::Z);
+
+        self.start_warp(WarpPoint {
+            name: planet.name.clone(),
+            body: Some(planet.name.clone()),
+            position: dir * (planet.radius() * 4.0 + 2.0),
+            target: Vec3::ZERO,
+            body_index: Some(body),
+        });
+    }
+
+    fn start_warp(&mut self, warp: WarpPoint) {
+        if !self.is_warping {
+            self.orbit = None;
+            self.path_time = None;
+            self.current_warp = Some(warp);
             self.is_warping = true;
             self.warp_progress = 0.0;
-            self.warp_start_pos = self.camera.position;
-            self.warp_start_yaw = self.camera.yaw;
-            self.warp_start_pitch = self.camera.pitch;
+            let start = self.controlled_pose();
+            self.warp_start_pos = start.position;
+            self.warp_start_orientation = start.orientation;
+        }
+    }
+
+    /// Avanza la simulación con el tiempo real transcurrido desde el último frame,
+    /// en tantos pasos de `FIXED_STEP` como quepan.
+    pub fn update(&mut self, frame_dt: Duration) {
+        self.hud.update(frame_dt.as_secs_f32());
+
+        if let Some(watcher) = &mut self.shader_watcher {
+            for file in watcher.changed_files() {
+                self.state.reload_shader(watcher.dir(), &file);
+            }
+        }
+
+        for _ in 0..self.timestep.steps(frame_dt) {
+            if let Some(replay) = &mut self.replay {
+                for event in replay.events_for_step(self.step_count) {
+                    self.apply_input(event);
+                }
+            }
+            self.step(FIXED_STEP.as_secs_f32());
+            self.step_count += 1;
+
+            if self.replay.as_ref().is_some_and(|replay| replay.finished(self.step_count)) {
+                println!("[replay] terminado en el paso {}", self.step_count);
+                self.replay = None;
+                // Durante la reproducción el cursor no se captura: hacerlo ahora
+                // si la sesión terminó mirando con el ratón
+                let mouse_look = std::mem::take(&mut self.mouse_look);
+                self.set_mouse_look(mouse_look);
+            }
         }
     }
 
-    pub fn update(&mut self, dt: std::time::Duration) {
-        let dt = dt.as_secs_f32();
-        self.time += dt;
+    /// Un paso fijo de la simulación: relojes, cuerpos, entradas y cámara.
+    fn step(&mut self, dt: f32) {
+        self.previous_pose = self.camera.pose();
+        self.previous_ship_pose = self.ship.pose();
+        let time_before = self.clock.time;
+        self.clock.advance(dt);
+        if let Some(shader_clock) = &mut self.shader_clock {
+            shader_clock.advance(dt);
+        }
+
+        // Primero los cuerpos, para que warp y cámara orbital usen su posición actual
+        if let Some(gravity) = &mut self.gravity {
+            gravity.step(self.clock.time - time_before);
+        }
+        self.update_bodies(1.0);
 
+        // El camino de cámara manda sobre todo lo demás, incluidas las colisiones
+        if self.path_time.is_some() {
+            self.pending_mouse = Vec2::ZERO;
+            self.update_camera_path(dt);
+            return;
+        }
+
+        // La nave sigue volando con su inercia aunque no se la controle
+        let mut controls = ShipControls::default();
         if self.is_warping {
             self.update_warp(dt);
+        } else if self.orbit.is_some() {
+            self.apply_mouse_motion();
+            self.update_orbit_keys(dt);
+        } else if self.ship_view.is_some() {
+            self.apply_mouse_motion();
+            controls = self.ship_controls();
         } else {
+            self.apply_mouse_motion();
             self.update_camera(dt);
         }
-        
-        self.check_collisions();
-        
-        for planet in &mut self.planets {
-            planet.update(self.time);
+        if !self.is_warping {
+            let gravity = self.gravity.as_ref().map_or(Vec3::ZERO, |gravity| gravity.acceleration_at(self.ship.position));
+            self.ship.update(&controls, gravity, dt);
+        }
+        if let Some(contact) = collision::resolve(&self.planets, &mut self.ship.position, self.collision.margin) {
+            if let Some(event) = collision::respond(&mut self.ship.velocity, &contact, &self.collision) {
+                self.on_collision(event);
+            }
+        }
+
+        // La cámara orbital sigue la posición ya actualizada de su cuerpo
+        if let Some(orbit) = &self.orbit {
+            orbit.apply(&mut self.camera, self.planets[orbit.body].get_position());
+        } else if let Some(view) = self.ship_view {
+            self.ship.follow(&mut self.camera, view, dt);
+            if view == ShipView::Chase {
+                self.check_collisions();
+            }
+        } else {
+            self.check_collisions();
+        }
+    }
+
+    /// Coloca los cuerpos entre el paso anterior (`alpha` = 0) y el actual
+    /// (`alpha` = 1): por sus órbitas o por la integración del modo gravedad.
+    fn update_bodies(&mut self, alpha: f32) {
+        let time = self.clock.interpolated(alpha);
+        match &self.gravity {
+            Some(gravity) => gravity.apply(&mut self.planets, alpha, time),
+            None => planets::update_system(&mut self.planets, time),
+        }
+    }
+
+    /// Avanza por el camino de cámara. Sin bucle, se detiene en la última clave.
+    fn update_camera_path(&mut self, dt: f32) {
+        let (Some(camera_path), Some(time)) = (&self.camera_path, self.path_time) else {
+            return;
+        };
+        let time = time + dt;
+
+        let (position, direction) = camera_path.sample(time, &self.planets);
+        self.camera.position = position;
+        self.camera.look_towards(direction);
+
+        if !camera_path.looped && time >= camera_path.duration() {
+            println!("[camino] terminado");
+            self.path_time = None;
+        } else {
+            self.path_time = Some(time);
         }
     }
 
+    /// Teclado en modo órbita: flechas giran, W/S acercan y alejan, A/D y
+    /// Espacio/Shift desplazan el punto de mira.
+    fn update_orbit_keys(&mut self, dt: f32) {
+        let Some(orbit) = &mut self.orbit else {
+            return;
+        };
+
+        let axis = |positive: bool, negative: bool| (positive as i32 - negative as i32) as f32;
+
+        orbit.rotate(
+            axis(self.rotate_left, self.rotate_right) * dt * 15.0,
+            axis(self.rotate_down, self.rotate_up) * dt * 15.0,
+            self.camera.sensitivity,
+        );
+        orbit.zoom(axis(self.move_forward, self.move_backward) * dt * 5.0);
+        orbit.pan(
+            &self.camera,
+            axis(self.move_right, self.move_left) * dt * 0.5,
+            axis(self.move_up, self.move_down) * dt * 0.5,
+        );
+    }
+
     fn update_warp(&mut self, dt: f32) {
-        self.warp_progress += dt * 1.5;
-        
-        if self.warp_progress >= 1.0 {
-            self.warp_progress = 1.0;
+        let Some(warp) = &self.current_warp else {
+            self.is_warping = false;
+            return;
+        };
+        self.warp_progress = (self.warp_progress + dt * 1.5).min(1.0);
+
+        // El destino se recalcula cada frame para seguir al cuerpo si se mueve
+        let (target_position, look_at) = warp.resolve(&self.planets);
+        // Llegada con el horizonte nivelado; el giro (incluido el alabeo) se
+        // interpola directamente entre cuaterniones
+        let target_orientation = look_rotation(look_at - target_position, Vec3::Y);
+
+        let pose = if self.warp_progress >= 1.0 {
             self.is_warping = false;
-            let target_point = &self.warp_points[self.current_warp];
-            self.camera.position = target_point.position;
-            let dir = (target_point.target - target_point.position).normalize();
-            self.camera.yaw = dir.x.atan2(dir.z);
-            self.camera.pitch = dir.y.asin();
+            CameraPose {
+                position: target_position,
+                orientation: target_orientation,
+            }
         } else {
-            let t = ease_in_out_cubic(self.warp_progress);
-            let target_point = &self.warp_points[self.current_warp];
-            
-            self.camera.position = self.warp_start_pos.lerp(target_point.position, t);
-            
-            let start_dir = Vec3::new(
-                self.warp_start_yaw.cos() * self.warp_start_pitch.cos(),
-                self.warp_start_pitch.sin(),
-                self.warp_start_yaw.sin() * self.warp_start_pitch.cos(),
-            );
-            let target_dir = (target_point.target - target_point.position).normalize();
-            
-            let start_quat = Quat::from_rotation_arc(Vec3::Z, start_dir);
-            let target_quat = Quat::from_rotation_arc(Vec3::Z, target_dir);
-            let current_quat = start_quat.slerp(target_quat, t);
-            let current_dir = current_quat * Vec3::Z;
-            
-            self.camera.yaw = current_dir.x.atan2(current_dir.z);
-            self.camera.pitch = current_dir.y.asin();
-        }
+            let t = Easing::EaseInOut.apply(self.warp_progress);
+            CameraPose {
+                position: self.warp_start_pos.lerp(target_position, t),
+                orientation: self.warp_start_orientation.slerp(target_orientation, t),
+            }
+        };
+        self.set_controlled_pose(pose);
     }
 
     fn update_camera(&mut self, dt: f32) {
@@ -195,7 +899,8 @@ impl Renderer {
 
         let mut delta_yaw = 0.0;
         let mut delta_pitch = 0.0;
-        
+        let mut delta_roll = 0.0;
+
         if self.rotate_left {
             delta_yaw -= dt * 2.0;
         }
@@ -208,31 +913,117 @@ impl Renderer {
         if self.rotate_down {
             delta_pitch -= dt * 2.0;
         }
-        
+        if self.roll_left {
+            delta_roll -= dt * 2.0;
+        }
+        if self.roll_right {
+            delta_roll += dt * 2.0;
+        }
+
         if delta_yaw != 0.0 || delta_pitch != 0.0 {
             self.camera.rotate(delta_yaw, delta_pitch);
         }
+        if delta_roll != 0.0 {
+            self.camera.roll(delta_roll);
+        }
     }
 
+    /// Mandos de la nave con las mismas teclas que la cámara libre: WASD,
+    /// Espacio/Shift para empujar, flechas y Q/E para girar, Tab para el turbo.
+    fn ship_controls(&self) -> ShipControls {
+        let axis = |positive: bool, negative: bool| (positive as i32 - negative as i32) as f32;
+
+        ShipControls {
+            thrust: Vec3::new(
+                axis(self.move_right, self.move_left),
+                axis(self.move_up, self.move_down),
+                axis(self.move_backward, self.move_forward),
+            ),
+            yaw: axis(self.rotate_right, self.rotate_left),
+            pitch: axis(self.rotate_up, self.rotate_down),
+            roll: axis(self.roll_right, self.roll_left),
+            boost: self.boost,
+        }
+    }
+
+    /// Vista desde la cabina sin que otro modo se haya llevado la cámara.
+    fn in_cockpit(&self) -> bool {
+        self.ship_view == Some(ShipView::Cockpit) && self.orbit.is_none() && self.path_time.is_none()
+    }
+
+    /// Mantiene la cámara fuera de cuerpos y anillos (sin rebote: se desliza).
     fn check_collisions(&mut self) {
-        let min_distance = 5.0;
-        
-        for planet in &self.planets {
-            let planet_pos = planet.get_position();
-            let distance = (self.camera.position - planet_pos).length();
-            let collision_radius = planet.scale + min_distance;
-            
-            if distance < collision_radius {
-                let push_dir = (self.camera.position - planet_pos).normalize();
-                self.camera.position = planet_pos + push_dir * collision_radius;
-            }
+        collision::resolve(&self.planets, &mut self.camera.position, self.collision.margin);
+    }
+
+    /// Reacción a un choque de la nave: daño al casco, aviso en el HUD y log.
+    fn on_collision(&mut self, event: CollisionEvent) {
+        let name = &self.planets[event.body].name;
+        let target = match event.surface {
+            Surface::Body => name.clone(),
+            Surface::Rings => format!("los anillos de {}", name),
+        };
+
+        if event.damage > 0.0 {
+            let destroyed = self.ship.damage(event.damage);
+            println!(
+                "[colision] choque con {} a {:.1}: daño {:.0}, casco {:.0}%",
+                target, event.impact_speed, event.damage, self.ship.hull
+            );
+            self.hud.notify(if destroyed {
+                "Casco destruido".to_string()
+            } else {
+                format!("Choque con {} (-{:.0}%)", target, event.damage)
+            });
+        } else {
+            println!("[colision] rebote contra {} a {:.1}", target, event.impact_speed);
+            self.hud.notify(format!("Rebote contra {}", target));
         }
     }
 
     pub fn render(&mut self) -> Result<(), wgpu::SurfaceError> {
-        let output = self.state.surface.get_current_texture()?;
+        let Some(surface) = &self.state.surface else {
+            // Modo headless: dibujar directamente en la textura offscreen
+            let view = self.state.offscreen_texture.as_ref()
+                .expect("renderer headless sin textura offscreen")
+                .create_view(&wgpu::TextureViewDescriptor::default());
+            self.draw(&view);
+            return Ok(());
+        };
+
+        let output = surface.get_current_texture()?;
         let view = output.texture.create_view(&wgpu::TextureViewDescriptor::default());
+        self.draw(&view);
+        output.present();
+
+        Ok(())
+    }
+
+    /// Lee el último frame renderizado en modo headless como píxeles RGBA8.
+    pub fn capture_frame(&self) -> Option<Vec<u8>> {
+        self.state.read_frame()
+    }
+
+    /// Dibuja el estado interpolado entre los dos últimos pasos de la simulación.
+    fn draw(&mut self, view: &wgpu::TextureView) {
+        let alpha = self.timestep.alpha();
+        let step_pose = self.camera.pose();
+        self.camera.set_pose(self.previous_pose.lerp(step_pose, alpha));
+        let step_ship_pose = self.ship.pose();
+        let ship_pose = self.previous_ship_pose.lerp(step_ship_pose, alpha);
+        (self.ship.position, self.ship.orientation) = (ship_pose.position, ship_pose.orientation);
+        self.update_bodies(alpha);
 
+        self.encode_frame(view, self.shader_time(alpha));
+
+        // Volver al estado del paso: las entradas entre frames (picking, warp)
+        // deben ver lo mismo al grabar que al reproducir
+        self.camera.set_pose(step_pose);
+        (self.ship.position, self.ship.orientation) = (step_ship_pose.position, step_ship_pose.orientation);
+        self.update_bodies(1.0);
+    }
+
+    fn encode_frame(&mut self, view: &wgpu::TextureView, time: f32) {
         let mut encoder = self.state.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
             label: Some("Render Encoder"),
         });
@@ -243,7 +1034,7 @@ impl Renderer {
         let skybox_uniforms = Uniforms {
             view_proj: view_proj.to_cols_array_2d(),
             model: Mat4::from_translation(self.camera.position).to_cols_array_2d(),
-            time: self.time,
+            time,
             _padding: [0.0; 3],
         };
         self.state.queue.write_buffer(
@@ -252,12 +1043,33 @@ impl Renderer {
             bytemuck::cast_slice(&[skybox_uniforms])
         );
 
+        // Actualizar la luz: el sol es el primer cuerpo de tipo Sun (o el origen si no hay)
+        let sun_position = self.planets.iter()
+            .find(|planet| planet.planet_type == PlanetType::Sun)
+            .map(|sun| sun.get_position())
+            .unwrap_or(Vec3::ZERO);
+        let light_uniforms = LightUniforms {
+            sun_position: sun_position.to_array(),
+            intensity: self.light.intensity,
+            sun_color: self.light.color.to_array(),
+            ambient: self.light.ambient,
+            camera_position: self.camera.position.to_array(),
+            shadow_far: shadows::SHADOW_FAR,
+        };
+        self.state.queue.write_buffer(
+            &self.state.light_uniform_buffer,
+            0,
+            bytemuck::cast_slice(&[light_uniforms])
+        );
+        self.state.shadow_map.update(&self.state.queue, sun_position);
+        self.state.post.update(&self.state.queue, &self.post);
+
         // Actualizar uniformes de planetas
         for (i, planet) in self.planets.iter().enumerate() {
             let uniforms = Uniforms {
                 view_proj: view_proj.to_cols_array_2d(),
                 model: planet.get_model_matrix().to_cols_array_2d(),
-                time: self.time,
+                time,
                 _padding: [0.0; 3],
             };
             self.state.queue.write_buffer(
@@ -265,18 +1077,46 @@ impl Renderer {
                 0,
                 bytemuck::cast_slice(&[uniforms])
             );
+
+            let orbit_uniforms = Uniforms {
+                view_proj: view_proj.to_cols_array_2d(),
+                model: planet.get_parent_matrix().to_cols_array_2d(),
+                time,
+                _padding: [0.0; 3],
+            };
+            self.state.queue.write_buffer(
+                &self.state.orbit_uniform_buffers[i],
+                0,
+                bytemuck::cast_slice(&[orbit_uniforms])
+            );
+        }
+
+        // Actualizar uniformes de props (giran con el tiempo de la simulación)
+        let sim_time = self.clock.interpolated(self.timestep.alpha());
+        for (i, prop) in self.props.iter().enumerate() {
+            let uniforms = Uniforms {
+                view_proj: view_proj.to_cols_array_2d(),
+                model: prop.model_matrix(&self.planets, sim_time).to_cols_array_2d(),
+                time,
+                _padding: [0.0; 3],
+            };
+            self.state.queue.write_buffer(
+                &self.state.prop_uniform_buffers[i],
+                0,
+                bytemuck::cast_slice(&[uniforms])
+            );
         }
 
         // Actualizar uniformes de la nave
-        let ship_model = Mat4::from_translation(self.camera.position + self.camera.get_forward() * 3.0 + Vec3::new(0.5, -0.5, 0.0))
-            * Mat4::from_rotation_y(self.camera.yaw + std::f32::consts::PI)
-            * Mat4::from_rotation_x(-self.camera.pitch)
-            * Mat4::from_scale(Vec3::splat(0.5));
+        // La malla de la nave apunta hacia +Z; media vuelta para que mire hacia -Z local
+        let ship_model = Mat4::from_translation(self.ship.position)
+            * Mat4::from_quat(self.ship.orientation * Quat::from_rotation_y(std::f32::consts::PI))
+            * self.ship_model.unwrap_or(Mat4::from_scale(Vec3::splat(0.5)));
         
         let ship_uniforms = Uniforms {
             view_proj: view_proj.to_cols_array_2d(),
             model: ship_model.to_cols_array_2d(),
-            time: self.time,
+            time,
             _padding: [0.0; 3],
         };
         self.state.queue.write_buffer(
@@ -285,11 +1125,13 @@ impl Renderer {
             bytemuck::cast_slice(&[ship_uniforms])
         );
 
+        self.draw_shadows(&mut encoder);
+
         {
             let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: Some("Render Pass"),
                 color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: &view,
+                    view: self.state.post.hdr_view(),
                     resolve_target: None,
                     ops: wgpu::Operations {
                         load: wgpu::LoadOp::Clear(wgpu::Color {
@@ -316,6 +1158,9 @@ impl Renderer {
             // Renderizar skybox primero
             render_pass.set_pipeline(&self.state.skybox_pipeline);
             render_pass.set_bind_group(0, &self.state.skybox_bind_group, &[]);
+            if let Some(sky) = &self.state.sky {
+                render_pass.set_bind_group(1, &sky.bind_group, &[]);
+            }
             render_pass.set_vertex_buffer(0, self.state.skybox_vertex_buffer.slice(..));
             render_pass.set_index_buffer(self.state.skybox_index_buffer.slice(..), wgpu::IndexFormat::Uint32);
             render_pass.draw_indexed(0..self.state.skybox_num_indices, 0, 0..1);
@@ -326,7 +1171,7 @@ impl Renderer {
             render_pass.set_index_buffer(self.state.orbit_index_buffer.slice(..), wgpu::IndexFormat::Uint32);
             
             for (i, planet) in self.planets.iter().enumerate() {
-                if planet.orbit_radius > 0.0 {
+                if planet.orbit.is_some() {
                     let (start, count) = self.state.orbit_ranges[i];
                     if count > 0 {
                         render_pass.set_bind_group(0, &self.state.orbit_bind_groups[i], &[]);
@@ -343,36 +1188,17 @@ impl Renderer {
                 let pipeline = &self.state.planet_pipelines[planet.planet_type as usize];
                 render_pass.set_pipeline(pipeline);
                 render_pass.set_bind_group(0, &self.state.planet_bind_groups[i], &[]);
+                render_pass.set_bind_group(1, &self.state.light_bind_group, &[]);
+                render_pass.set_bind_group(2, &self.state.planet_materials[i].bind_group, &[]);
                 render_pass.draw_indexed(0..self.state.num_indices, 0, 0..1);
                 
-                // Renderizar luna si existe
-                if planet.has_moon {
-                    if let Some(moon_matrix) = planet.get_moon_model_matrix() {
-                        let moon_uniforms = Uniforms {
-                            view_proj: view_proj.to_cols_array_2d(),
-                            model: moon_matrix.to_cols_array_2d(),
-                            time: self.time,
-                            _padding: [0.0; 3],
-                        };
-                        self.state.queue.write_buffer(
-                            &self.state.moon_uniform_buffers[i],
-                            0,
-                            bytemuck::cast_slice(&[moon_uniforms])
-                        );
-                        
-                        render_pass.set_pipeline(&self.state.moon_pipeline);
-                        render_pass.set_bind_group(0, &self.state.moon_bind_groups[i], &[]);
-                        render_pass.draw_indexed(0..self.state.num_indices, 0, 0..1);
-                    }
-                }
-                
                 // Renderizar anillos si existen
                 if planet.has_rings {
                     if let Some(ring_matrix) = planet.get_rings_model_matrix() {
                         let ring_uniforms = Uniforms {
                             view_proj: view_proj.to_cols_array_2d(),
                             model: ring_matrix.to_cols_array_2d(),
-                            time: self.time,
+                            time,
                             _padding: [0.0; 3],
                         };
                         self.state.queue.write_buffer(
@@ -383,6 +1209,7 @@ impl Renderer {
                         
                         render_pass.set_pipeline(&self.state.ring_pipeline);
                         render_pass.set_bind_group(0, &self.state.ring_bind_groups[i], &[]);
+                        render_pass.set_bind_group(1, &self.state.light_bind_group, &[]);
                         render_pass.set_vertex_buffer(0, self.state.ring_vertex_buffer.slice(..));
                         render_pass.set_index_buffer(self.state.ring_index_buffer.slice(..), wgpu::IndexFormat::Uint32);
                         render_pass.draw_indexed(0..self.state.ring_num_indices, 0, 0..1);
@@ -393,25 +1220,96 @@ impl Renderer {
                 }
             }
 
-            // Renderizar nave
-            render_pass.set_pipeline(&self.state.ship_pipeline);
-            render_pass.set_bind_group(0, &self.state.ship_bind_group, &[]);
-            render_pass.set_vertex_buffer(0, self.state.ship_vertex_buffer.slice(..));
-            render_pass.set_index_buffer(self.state.ship_index_buffer.slice(..), wgpu::IndexFormat::Uint32);
-            render_pass.draw_indexed(0..self.state.ship_num_indices, 0, 0..1);
+            // Renderizar props
+            render_pass.set_pipeline(&self.state.prop_pipeline);
+            render_pass.set_bind_group(1, &self.state.light_bind_group, &[]);
+            for (i, model) in self.state.prop_models.iter().enumerate() {
+                render_pass.set_bind_group(0, &self.state.prop_bind_groups[i], &[]);
+                model.draw(&mut render_pass);
+            }
+
+            // Renderizar nave (no desde la cabina, donde la cámara está dentro)
+            if !self.in_cockpit() {
+                render_pass.set_bind_group(0, &self.state.ship_bind_group, &[]);
+                render_pass.set_bind_group(1, &self.state.light_bind_group, &[]);
+                match &self.state.ship_model {
+                    Some(model) => {
+                        render_pass.set_pipeline(&self.state.prop_pipeline);
+                        model.draw(&mut render_pass);
+                    }
+                    None => {
+                        render_pass.set_pipeline(&self.state.ship_pipeline);
+                        render_pass.set_vertex_buffer(0, self.state.ship_vertex_buffer.slice(..));
+                        render_pass.set_index_buffer(self.state.ship_index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+                        render_pass.draw_indexed(0..self.state.ship_num_indices, 0, 0..1);
+                    }
+                }
+            }
         }
 
-        self.state.queue.submit(std::iter::once(encoder.finish()));
-        output.present();
+        // Bloom y tonemapping del target HDR al destino final
+        self.state.post.run(&mut encoder, view, self.post.bloom);
 
-        Ok(())
+        // HUD encima del frame final
+        self.queue_hud();
+        self.state.text.render(
+            &self.state.device,
+            &self.state.queue,
+            &mut encoder,
+            view,
+            self.size.width,
+            self.size.height,
+        );
+
+        self.state.queue.submit(std::iter::once(encoder.finish()));
     }
 }
 
-fn ease_in_out_cubic(t: f32) -> f32 {
-    if t < 0.5 {
-        4.0 * t * t * t
-    } else {
-        1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+impl Renderer {
+    /// Renderiza planetas, lunas, anillos y props en las 6 caras del mapa de sombras del sol.
+    /// El sol no proyecta sombra (la luz está en su interior).
+    fn draw_shadows(&self, encoder: &mut wgpu::CommandEncoder) {
+        for face in 0..6 {
+            let mut shadow_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Shadow Pass"),
+                color_attachments: &[],
+                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                    view: &self.state.shadow_map.face_views[face],
+                    depth_ops: Some(wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(1.0),
+                        store: wgpu::StoreOp::Store,
+                    }),
+                    stencil_ops: None,
+                }),
+                occlusion_query_set: None,
+                timestamp_writes: None,
+            });
+
+            shadow_pass.set_pipeline(&self.state.shadow_pipeline);
+            shadow_pass.set_bind_group(1, &self.state.shadow_map.face_bind_groups[face], &[]);
+
+            shadow_pass.set_vertex_buffer(0, self.state.vertex_buffer.slice(..));
+            shadow_pass.set_index_buffer(self.state.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+            for (i, planet) in self.planets.iter().enumerate() {
+                if planet.planet_type != PlanetType::Sun {
+                    shadow_pass.set_bind_group(0, &self.state.planet_bind_groups[i], &[]);
+                    shadow_pass.draw_indexed(0..self.state.num_indices, 0, 0..1);
+                }
+            }
+
+            shadow_pass.set_vertex_buffer(0, self.state.ring_vertex_buffer.slice(..));
+            shadow_pass.set_index_buffer(self.state.ring_index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+            for (i, planet) in self.planets.iter().enumerate() {
+                if planet.has_rings {
+                    shadow_pass.set_bind_group(0, &self.state.ring_bind_groups[i], &[]);
+                    shadow_pass.draw_indexed(0..self.state.ring_num_indices, 0, 0..1);
+                }
+            }
+
+            for (i, model) in self.state.prop_models.iter().enumerate() {
+                shadow_pass.set_bind_group(0, &self.state.prop_bind_groups[i], &[]);
+                model.draw_shadow(&mut shadow_pass);
+            }
+        }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,518 @@
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use glam::{EulerRot, Mat3, Mat4, Quat, Vec3, Vec4};
+use serde::Deserialize;
+use wgpu::util::DeviceExt;
+
+use super::geometry::{generate_normals, generate_tangents};
+use super::material::{Material, MaterialSettings, TextureData};
+use super::planets::Planet;
+use super::types::Vertex;
+
+/// Modelo 3D de un archivo glTF 2.0 (`.gltf`/`.glb`) u OBJ, con el giro y la
+/// escala con que se coloca. Los modelos miran hacia +Z con +Y arriba (como en glTF).
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ModelSettings {
+    /// Ruta relativa al archivo de escena
+    pub path: PathBuf,
+    #[serde(default = "default_scale")]
+    pub scale: f32,
+    /// Giro del modelo (ángulos de Euler XYZ en radianes), por ejemplo para los
+    /// que no miran hacia +Z
+    #[serde(default)]
+    pub rotation: Vec3,
+    /// Malla ya leída por `load_mesh`
+    #[serde(skip)]
+    pub mesh: Option<MeshData>,
+}
+
+fn default_scale() -> f32 {
+    1.0
+}
+
+impl ModelSettings {
+    pub fn load_mesh(&mut self, base_dir: &Path) -> Result<(), String> {
+        let path = base_dir.join(&self.path);
+        let mesh = MeshData::load(&path).map_err(|message| format!("no se pudo cargar {}: {}", path.display(), message))?;
+        println!(
+            "[modelo] {}: {} vértices, {} triángulos, {} materiales",
+            path.display(),
+            mesh.vertices.len(),
+            mesh.indices.len() / 3,
+            mesh.materials.len()
+        );
+        self.mesh = Some(mesh);
+        Ok(())
+    }
+
+    /// Giro y escala del modelo, antes de colocarlo en el mundo.
+    pub fn local_matrix(&self) -> Mat4 {
+        let rotation = Quat::from_euler(EulerRot::XYZ, self.rotation.x, self.rotation.y, self.rotation.z);
+        Mat4::from_quat(rotation) * Mat4::from_scale(Vec3::splat(self.scale))
+    }
+}
+
+/// Objeto de decorado (asteroide, estación...). Como los puntos de warp, con
+/// `body` la posición se mide desde ese cuerpo y lo acompaña en su órbita.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Prop {
+    pub name: String,
+    pub model: ModelSettings,
+    #[serde(default)]
+    pub body: Option<String>,
+    #[serde(default)]
+    pub position: Vec3,
+    /// Giro propio alrededor de su eje Y en radianes por segundo
+    #[serde(default)]
+    pub spin: f32,
+    /// Índice de `body` en la lista aplanada (se resuelve al cargar la escena)
+    #[serde(skip)]
+    pub body_index: Option<usize>,
+}
+
+impl Prop {
+    pub fn model_matrix(&self, planets: &[Planet], time: f32) -> Mat4 {
+        let frame = self.body_index.map_or(Mat4::IDENTITY, |index| planets[index].get_frame_matrix());
+        frame
+            * Mat4::from_translation(self.position)
+            * Mat4::from_rotation_y(self.spin * time)
+            * self.model.local_matrix()
+    }
+}
+
+/// Parte de la malla que se dibuja con un mismo material.
+pub struct Primitive {
+    pub indices: Range<u32>,
+    /// Índice en `MeshData::materials`
+    pub material: usize,
+}
+
+/// Malla importada en memoria: un solo buffer de vértices e índices con las
+/// transformaciones de los nodos ya aplicadas, partida por material.
+#[derive(Default)]
+pub struct MeshData {
+    pub vertices: Vec<Vertex>,
+    pub indices: Vec<u32>,
+    pub primitives: Vec<Primitive>,
+    pub materials: Vec<MaterialSettings>,
+}
+
+impl MeshData {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_ascii_lowercase();
+        let mesh = match extension.as_str() {
+            "gltf" | "glb" => Self::from_gltf(path)?,
+            "obj" => Self::from_obj(path)?,
+            _ => return Err("formato no soportado (usa .gltf, .glb u .obj)".to_string()),
+        };
+        if mesh.indices.is_empty() {
+            return Err("el modelo no tiene triángulos".to_string());
+        }
+        Ok(mesh)
+    }
+
+    /// Añade una primitiva en espacio local transformada por `transform`. Si
+    /// faltan normales o tangentes se calculan antes de transformarla; sin
+    /// normales la malla se separa por caras para sombrearla plana (como pide glTF).
+    fn push(
+        &mut self,
+        mut vertices: Vec<Vertex>,
+        mut indices: Vec<u32>,
+        has_normals: bool,
+        has_tangents: bool,
+        transform: Mat4,
+        material: usize,
+    ) {
+        if !has_normals {
+            vertices = indices.iter().map(|&index| vertices[index as usize]).collect();
+            indices = (0..vertices.len() as u32).collect();
+            generate_normals(&mut vertices, &indices);
+        }
+        if !has_tangents {
+            generate_tangents(&mut vertices, &indices);
+        }
+
+        let linear = Mat3::from_mat4(transform);
+        let normal_matrix = linear.inverse().transpose();
+        // Una transformación con reflejo invierte el orden de los triángulos y la bitangente
+        let mirrored = linear.determinant() < 0.0;
+        for vertex in &mut vertices {
+            vertex.position = transform.transform_point3(Vec3::from(vertex.position)).to_array();
+            vertex.normal = (normal_matrix * Vec3::from(vertex.normal)).normalize_or(Vec3::Y).to_array();
+            let tangent = Vec4::from(vertex.tangent);
+            let direction = (linear * tangent.truncate()).normalize_or(Vec3::X);
+            let handedness = if mirrored { -tangent.w } else { tangent.w };
+            vertex.tangent = direction.extend(handedness).to_array();
+        }
+        if mirrored {
+            for triangle in indices.chunks_exact_mut(3) {
+                triangle.swap(1, 2);
+            }
+        }
+
+        let base_vertex = self.vertices.len() as u32;
+        let first_index = self.indices.len() as u32;
+        self.vertices.extend(vertices);
+        self.indices.extend(indices.iter().map(|index| index + base_vertex));
+        self.primitives.push(Primitive {
+            indices: first_index..self.indices.len() as u32,
+            material,
+        });
+    }
+
+    fn from_gltf(path: &Path) -> Result<Self, String> {
+        let (document, buffers, images) = gltf::import(path).map_err(|e| e.to_string())?;
+        let scene = document
+            .default_scene()
+            .or_else(|| document.scenes().next())
+            .ok_or("el archivo no tiene escenas")?;
+
+        let mut mesh = Self {
+            materials: document
+                .materials()
+                .map(|material| gltf_material(&material, &images))
+                .collect::<Result<_, _>>()?,
+            ..Default::default()
+        };
+        // Material por defecto de glTF (blanco mate) para las primitivas sin material
+        let default_material = mesh.materials.len();
+        mesh.materials.push(plain_material([1.0; 4], 0.0));
+
+        let mut nodes: Vec<(gltf::Node, Mat4)> = scene.nodes().map(|node| (node, Mat4::IDENTITY)).collect();
+        while let Some((node, parent)) = nodes.pop() {
+            let transform = parent * Mat4::from_cols_array_2d(&node.transform().matrix());
+            nodes.extend(node.children().map(|child| (child, transform)));
+
+            let Some(node_mesh) = node.mesh() else {
+                continue;
+            };
+            for primitive in node_mesh.primitives() {
+                if primitive.mode() != gltf::mesh::Mode::Triangles {
+                    println!("[modelo] {}: se ignora una primitiva que no es de triángulos", path.display());
+                    continue;
+                }
+                let reader = primitive.reader(|buffer| Some(&buffers[buffer.index()]));
+                let Some(positions) = reader.read_positions() else {
+                    continue;
+                };
+
+                let mut vertices: Vec<Vertex> = positions
+                    .map(|position| Vertex {
+                        position,
+                        ..Default::default()
+                    })
+                    .collect();
+                let normals = reader.read_normals();
+                let has_normals = normals.is_some();
+                for (vertex, normal) in vertices.iter_mut().zip(normals.into_iter().flatten()) {
+                    vertex.normal = normal;
+                }
+                if let Some(uvs) = reader.read_tex_coords(0) {
+                    for (vertex, uv) in vertices.iter_mut().zip(uvs.into_f32()) {
+                        vertex.uv = uv;
+                    }
+                }
+                let tangents = reader.read_tangents();
+                let has_tangents = tangents.is_some();
+                for (vertex, tangent) in vertices.iter_mut().zip(tangents.into_iter().flatten()) {
+                    vertex.tangent = tangent;
+                }
+                let indices = match reader.read_indices() {
+                    Some(indices) => indices.into_u32().collect(),
+                    None => (0..vertices.len() as u32).collect(),
+                };
+
+                let material = primitive.material().index().unwrap_or(default_material);
+                mesh.push(vertices, indices, has_normals, has_tangents, transform, material);
+            }
+        }
+
+        Ok(mesh)
+    }
+
+    fn from_obj(path: &Path) -> Result<Self, String> {
+        let (models, materials) = tobj::load_obj(path, &tobj::GPU_LOAD_OPTIONS).map_err(|e| e.to_string())?;
+        let materials = materials.unwrap_or_else(|e| {
+            println!("[modelo] {}: sin materiales ({})", path.display(), e);
+            Vec::new()
+        });
+        let base_dir = path.parent().unwrap_or(Path::new(""));
+
+        let mut mesh = Self {
+            materials: materials
+                .iter()
+                .map(|material| obj_material(material, base_dir))
+                .collect::<Result<_, _>>()?,
+            ..Default::default()
+        };
+        let default_material = mesh.materials.len();
+        mesh.materials.push(plain_material([0.8, 0.8, 0.8, 1.0], 0.5));
+
+        for model in models {
+            let obj = model.mesh;
+            let count = obj.positions.len() / 3;
+            let has_normals = obj.normals.len() == obj.positions.len();
+            let has_uvs = obj.texcoords.len() / 2 == count;
+
+            let vertices = (0..count)
+                .map(|i| Vertex {
+                    position: [obj.positions[i * 3], obj.positions[i * 3 + 1], obj.positions[i * 3 + 2]],
+                    normal: if has_normals {
+                        [obj.normals[i * 3], obj.normals[i * 3 + 1], obj.normals[i * 3 + 2]]
+                    } else {
+                        [0.0; 3]
+                    },
+                    // En OBJ la V crece hacia arriba de la imagen
+                    uv: if has_uvs {
+                        [obj.texcoords[i * 2], 1.0 - obj.texcoords[i * 2 + 1]]
+                    } else {
+                        [0.0; 2]
+                    },
+                    tangent: [0.0; 4],
+                })
+                .collect();
+
+            let material = obj.material_id.filter(|&id| id < default_material).unwrap_or(default_material);
+            mesh.push(vertices, obj.indices, has_normals, false, Mat4::IDENTITY, material);
+        }
+
+        Ok(mesh)
+    }
+}
+
+/// Material de glTF (metallic-roughness) en términos de `MaterialSettings`: el
+/// factor de color se multiplica en la textura de albedo y el brillo especular
+/// sale de la rugosidad (1 - roughness).
+fn gltf_material(material: &gltf::Material, images: &[gltf::image::Data]) -> Result<MaterialSettings, String> {
+    let pbr = material.pbr_metallic_roughness();
+    let image = |texture: gltf::Texture| gltf_rgba(&images[texture.source().index()]);
+
+    let base_color = pbr.base_color_factor();
+    let albedo = match pbr.base_color_texture() {
+        Some(info) => {
+            let (mut rgba, width, height) = image(info.texture())?;
+            tint(&mut rgba, base_color);
+            TextureData::from_rgba8(rgba, width, height, true)
+        }
+        None => TextureData::solid(srgb_texel(base_color), true),
+    };
+
+    let normal_texture = material.normal_texture();
+    let normal_strength = normal_texture.as_ref().map_or(1.0, |texture| texture.scale());
+    let normal = match normal_texture {
+        Some(texture) => {
+            let (rgba, width, height) = image(texture.texture())?;
+            Some(TextureData::from_rgba8(rgba, width, height, false))
+        }
+        None => None,
+    };
+
+    let [r, g, b] = material.emissive_factor();
+    let emissive = match material.emissive_texture() {
+        Some(info) => {
+            let (mut rgba, width, height) = image(info.texture())?;
+            tint(&mut rgba, [r, g, b, 1.0]);
+            Some(TextureData::from_rgba8(rgba, width, height, true))
+        }
+        None if r + g + b > 0.0 => Some(TextureData::solid(srgb_texel([r, g, b, 1.0]), true)),
+        None => None,
+    };
+
+    let roughness = pbr.roughness_factor();
+    let specular = match pbr.metallic_roughness_texture() {
+        Some(info) => {
+            let (mut rgba, width, height) = image(info.texture())?;
+            // La rugosidad va en el canal verde
+            for texel in rgba.chunks_exact_mut(4) {
+                let shine = 255 - (texel[1] as f32 * roughness) as u8;
+                texel.copy_from_slice(&[shine, shine, shine, 255]);
+            }
+            TextureData::from_rgba8(rgba, width, height, false)
+        }
+        None => TextureData::solid(linear_texel(1.0 - roughness), false),
+    };
+
+    Ok(MaterialSettings {
+        normal_strength,
+        images: [Some(albedo), normal, emissive, Some(specular)],
+        ..Default::default()
+    })
+}
+
+/// Material de un archivo MTL: color difuso (`Kd`/`map_Kd`), normales
+/// (`norm`), brillo (`Ks`/`map_Ks`) y emisión (`map_Ke`), con rutas relativas al OBJ.
+fn obj_material(material: &tobj::Material, base_dir: &Path) -> Result<MaterialSettings, String> {
+    let load = |file: &str, srgb: bool| {
+        let path = base_dir.join(file);
+        TextureData::load(&path, srgb).map_err(|message| format!("{}: {}", path.display(), message))
+    };
+
+    let albedo = match &material.diffuse_texture {
+        Some(file) => load(file, true)?,
+        None => {
+            let [r, g, b] = material.diffuse.unwrap_or([0.8; 3]);
+            TextureData::solid(srgb_texel([r, g, b, 1.0]), true)
+        }
+    };
+    let normal = material.normal_texture.as_deref().map(|file| load(file, false)).transpose()?;
+    let emissive = material.unknown_param.get("map_Ke").map(|file| load(file, true)).transpose()?;
+    let specular = match &material.specular_texture {
+        Some(file) => load(file, false)?,
+        None => {
+            let [r, g, b] = material.specular.unwrap_or([0.5; 3]);
+            TextureData::solid(linear_texel((r + g + b) / 3.0), false)
+        }
+    };
+
+    Ok(MaterialSettings {
+        images: [Some(albedo), normal, emissive, Some(specular)],
+        ..Default::default()
+    })
+}
+
+fn plain_material(color: [f32; 4], specular: f32) -> MaterialSettings {
+    MaterialSettings {
+        images: [
+            Some(TextureData::solid(srgb_texel(color), true)),
+            None,
+            None,
+            Some(TextureData::solid(linear_texel(specular), false)),
+        ],
+        ..Default::default()
+    }
+}
+
+/// Imagen decodificada por el importador de glTF convertida a RGBA8.
+fn gltf_rgba(image: &gltf::image::Data) -> Result<(Vec<u8>, u32, u32), String> {
+    use gltf::image::Format;
+    let pixels = &image.pixels;
+    let rgba = match image.format {
+        Format::R8 => pixels.iter().flat_map(|&r| [r, r, r, 255]).collect(),
+        Format::R8G8 => pixels.chunks_exact(2).flat_map(|p| [p[0], p[1], 0, 255]).collect(),
+        Format::R8G8B8 => pixels.chunks_exact(3).flat_map(|p| [p[0], p[1], p[2], 255]).collect(),
+        Format::R8G8B8A8 => pixels.clone(),
+        // 16 bits por canal (little endian): se queda el byte alto
+        Format::R16G16B16 => pixels.chunks_exact(6).flat_map(|p| [p[1], p[3], p[5], 255]).collect(),
+        Format::R16G16B16A16 => pixels.chunks_exact(8).flat_map(|p| [p[1], p[3], p[5], p[7]]).collect(),
+        format => return Err(format!("formato de imagen {:?} no soportado", format)),
+    };
+    Ok((rgba, image.width, image.height))
+}
+
+/// Multiplica una imagen sRGB por un color lineal.
+fn tint(rgba: &mut [u8], color: [f32; 4]) {
+    if color == [1.0; 4] {
+        return;
+    }
+    for texel in rgba.chunks_exact_mut(4) {
+        for channel in 0..3 {
+            texel[channel] = linear_to_srgb(srgb_to_linear(texel[channel]) * color[channel]);
+        }
+        texel[3] = (texel[3] as f32 * color[3]).round() as u8;
+    }
+}
+
+fn srgb_texel(color: [f32; 4]) -> [u8; 4] {
+    [
+        linear_to_srgb(color[0]),
+        linear_to_srgb(color[1]),
+        linear_to_srgb(color[2]),
+        (color[3].clamp(0.0, 1.0) * 255.0).round() as u8,
+    ]
+}
+
+fn linear_texel(value: f32) -> [u8; 4] {
+    let value = (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+    [value, value, value, 255]
+}
+
+fn srgb_to_linear(value: u8) -> f32 {
+    let c = value as f32 / 255.0;
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(value: f32) -> u8 {
+    let c = value.clamp(0.0, 1.0);
+    let encoded = if c <= 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    };
+    (encoded * 255.0).round() as u8
+}
+
+/// Malla importada ya en la GPU, con sus materiales (grupo 2).
+pub struct Model {
+    pub vertex_buffer: wgpu::Buffer,
+    pub index_buffer: wgpu::Buffer,
+    pub num_indices: u32,
+    pub primitives: Vec<Primitive>,
+    pub materials: Vec<Material>,
+}
+
+impl Model {
+    pub fn new(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        material_layout: &wgpu::BindGroupLayout,
+        sampler: &wgpu::Sampler,
+        name: &str,
+        mesh: &MeshData,
+    ) -> Self {
+        let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some(&format!("{} Vertex Buffer", name)),
+            contents: bytemuck::cast_slice(&mesh.vertices),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+        let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some(&format!("{} Index Buffer", name)),
+            contents: bytemuck::cast_slice(&mesh.indices),
+            usage: wgpu::BufferUsages::INDEX,
+        });
+        let materials = mesh
+            .materials
+            .iter()
+            .map(|settings| Material::new(device, queue, material_layout, sampler, name, Some(settings)))
+            .collect();
+
+        Self {
+            vertex_buffer,
+            index_buffer,
+            num_indices: mesh.indices.len() as u32,
+            primitives: mesh
+                .primitives
+                .iter()
+                .map(|primitive| Primitive {
+                    indices: primitive.indices.clone(),
+                    material: primitive.material,
+                })
+                .collect(),
+            materials,
+        }
+    }
+
+    /// Dibuja todas las primitivas con su material (el pipeline y los grupos 0
+    /// y 1 ya deben estar puestos).
+    pub fn draw(&self, pass: &mut wgpu::RenderPass<'_>) {
+        pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+        pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+        for primitive in &self.primitives {
+            pass.set_bind_group(2, &self.materials[primitive.material].bind_group, &[]);
+            pass.draw_indexed(primitive.indices.clone(), 0, 0..1);
+        }
+    }
+
+    /// Dibuja la malla entera sin materiales (mapa de sombras).
+    pub fn draw_shadow(&self, pass: &mut wgpu::RenderPass<'_>) {
+        pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+        pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+        pass.draw_indexed(0..self.num_indices, 0, 0..1);
+    }
+}
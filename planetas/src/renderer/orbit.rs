@@ -0,0 +1,136 @@
+use glam::{Mat3, Vec3};
+use serde::Deserialize;
+use std::f32::consts::PI;
+
+/// Elementos orbitales keplerianos de un cuerpo respecto a su padre.
+///
+/// El plano de referencia es XZ (Y hacia arriba). Con todos los ángulos en 0 el
+/// periapsis está sobre +X y el cuerpo avanza hacia +Z. Ángulos en radianes,
+/// `mean_motion` en radianes por segundo.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OrbitalElements {
+    pub semi_major_axis: f32,
+    #[serde(default)]
+    pub eccentricity: f32,
+    #[serde(default)]
+    pub inclination: f32,
+    #[serde(default)]
+    pub longitude_of_ascending_node: f32,
+    #[serde(default)]
+    pub argument_of_periapsis: f32,
+    #[serde(default)]
+    pub mean_anomaly_at_epoch: f32,
+    #[serde(default)]
+    pub mean_motion: f32,
+}
+
+impl OrbitalElements {
+    /// Posición relativa al padre en el instante `time`.
+    pub fn position_at(&self, time: f32) -> Vec3 {
+        let mean_anomaly = self.mean_anomaly_at_epoch + self.mean_motion * time;
+        let eccentric_anomaly = solve_kepler(mean_anomaly, self.eccentricity);
+        self.position_from_eccentric_anomaly(eccentric_anomaly)
+    }
+
+    /// Punto de la elipse para una anomalía excéntrica dada. Se usa tanto para los
+    /// cuerpos como para dibujar la órbita, así que ambos coinciden siempre.
+    pub fn position_from_eccentric_anomaly(&self, eccentric_anomaly: f32) -> Vec3 {
+        let a = self.semi_major_axis;
+        let e = self.eccentricity;
+        let b = a * (1.0 - e * e).sqrt();
+
+        let perifocal = Vec3::new(
+            a * (eccentric_anomaly.cos() - e),
+            0.0,
+            b * eccentric_anomaly.sin(),
+        );
+
+        self.orientation() * perifocal
+    }
+
+    /// Rotación del plano orbital: nodo ascendente, inclinación y argumento del periapsis.
+    pub fn orientation(&self) -> Mat3 {
+        Mat3::from_rotation_y(-self.longitude_of_ascending_node)
+            * Mat3::from_rotation_x(-self.inclination)
+            * Mat3::from_rotation_y(-self.argument_of_periapsis)
+    }
+
+    /// Distancia mínima al padre.
+    pub fn periapsis(&self) -> f32 {
+        self.semi_major_axis * (1.0 - self.eccentricity)
+    }
+
+    /// Segundos por vuelta, o None si el cuerpo no se mueve (`mean_motion` = 0).
+    pub fn period(&self) -> Option<f32> {
+        (self.mean_motion != 0.0).then(|| 2.0 * PI / self.mean_motion.abs())
+    }
+}
+
+/// Resuelve la ecuación de Kepler `E - e sin(E) = M` con Newton-Raphson.
+pub fn solve_kepler(mean_anomaly: f32, eccentricity: f32) -> f32 {
+    // Llevar M a [-PI, PI] para que Newton converja rápido
+    let m = (mean_anomaly + PI).rem_euclid(2.0 * PI) - PI;
+    let mut e_anomaly = if eccentricity < 0.8 { m } else { PI * m.signum() };
+
+    for _ in 0..16 {
+        let f = e_anomaly - eccentricity * e_anomaly.sin() - m;
+        let df = 1.0 - eccentricity * e_anomaly.cos();
+        let delta = f / df;
+        e_anomaly -= delta;
+        if delta.abs() < 1e-6 {
+            break;
+        }
+    }
+
+    e_anomaly
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn orbit(semi_major_axis: f32, eccentricity: f32, mean_anomaly_at_epoch: f32) -> OrbitalElements {
+        OrbitalElements {
+            semi_major_axis,
+            eccentricity,
+            inclination: 0.3,
+            longitude_of_ascending_node: 1.1,
+            argument_of_periapsis: 0.7,
+            mean_anomaly_at_epoch,
+            mean_motion: 0.5,
+        }
+    }
+
+    #[test]
+    fn circular_orbit_keeps_its_radius() {
+        let orbit = orbit(5.0, 0.0, 0.0);
+        for step in 0..32 {
+            let distance = orbit.position_at(step as f32 * 0.7).length();
+            assert!((distance - 5.0).abs() < 1e-4, "{}", distance);
+        }
+    }
+
+    #[test]
+    fn periapsis_and_apoapsis_distances() {
+        let (a, e) = (10.0, 0.3);
+        let periapsis = orbit(a, e, 0.0).position_at(0.0).length();
+        let apoapsis = orbit(a, e, PI).position_at(0.0).length();
+        assert!((periapsis - a * (1.0 - e)).abs() < 1e-4, "{}", periapsis);
+        assert!((apoapsis - a * (1.0 + e)).abs() < 1e-4, "{}", apoapsis);
+        assert!((orbit(a, e, 0.0).periapsis() - periapsis).abs() < 1e-4);
+    }
+
+    #[test]
+    fn kepler_converges_for_high_eccentricity() {
+        let e = 0.99;
+        for step in -40..=40 {
+            let mean_anomaly = step as f32 * 0.41;
+            let eccentric_anomaly = solve_kepler(mean_anomaly, e);
+            // El residuo se compara con M llevado a [-PI, PI], como hace el solver
+            let m = (mean_anomaly + PI).rem_euclid(2.0 * PI) - PI;
+            let residual = eccentric_anomaly - e * eccentric_anomaly.sin() - m;
+            assert!(residual.abs() < 1e-4, "M = {}: residuo {}", mean_anomaly, residual);
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use glam::{Mat4, Vec2, Vec3, Vec4};
+
+use super::planets::Planet;
+
+/// Rayo en coordenadas del mundo que sale de la cámara y pasa por el píxel
+/// (`x`, `y`) de una pantalla `width` x `height`. Devuelve (origen, dirección unitaria).
+pub fn screen_ray(view_proj: Mat4, x: f32, y: f32, width: f32, height: f32) -> (Vec3, Vec3) {
+    let ndc_x = x / width * 2.0 - 1.0;
+    let ndc_y = 1.0 - y / height * 2.0;
+
+    // wgpu usa profundidad [0, 1]: 0 es el plano cercano y 1 el lejano
+    let inverse = view_proj.inverse();
+    let near = inverse.project_point3(Vec3::new(ndc_x, ndc_y, 0.0));
+    let far = inverse.project_point3(Vec3::new(ndc_x, ndc_y, 1.0));
+
+    (near, (far - near).normalize())
+}
+
+/// Distancia a lo largo del rayo hasta la primera intersección con la esfera, si
+/// la hay delante del origen. Si el origen está dentro, devuelve la salida.
+pub fn ray_sphere(origin: Vec3, dir: Vec3, center: Vec3, radius: f32) -> Option<f32> {
+    let to_center = center - origin;
+    let along = to_center.dot(dir);
+    let dist_sq = to_center.length_squared() - along * along;
+    let radius_sq = radius * radius;
+    if dist_sq > radius_sq {
+        return None;
+    }
+
+    let half_chord = (radius_sq - dist_sq).sqrt();
+    [along - half_chord, along + half_chord].into_iter().find(|&t| t >= 0.0)
+}
+
+/// Índice del cuerpo más cercano que corta el rayo (planetas y lunas por igual,
+/// ya que están en la misma lista aplanada).
+pub fn pick_body(planets: &[Planet], origin: Vec3, dir: Vec3) -> Option<usize> {
+    planets.iter()
+        .enumerate()
+        .filter_map(|(i, planet)| {
+            ray_sphere(origin, dir, planet.get_position(), planet.radius()).map(|t| (i, t))
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(i, _)| i)
+}
+
+/// Proyecta un punto del mundo a píxeles (origen arriba a la izquierda). Devuelve
+/// también la `w` del espacio de recorte (la profundidad de vista), o None si el
+/// punto queda detrás de la cámara.
+pub fn project(view_proj: Mat4, point: Vec3, width: f32, height: f32) -> Option<(Vec2, f32)> {
+    let clip = view_proj * Vec4::new(point.x, point.y, point.z, 1.0);
+    if clip.w <= 0.0 {
+        return None;
+    }
+
+    let ndc = clip.truncate() / clip.w;
+    let screen = Vec2::new((ndc.x + 1.0) * 0.5 * width, (1.0 - ndc.y) * 0.5 * height);
+    Some((screen, clip.w))
+}
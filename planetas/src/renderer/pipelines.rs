@@ -1,99 +1,226 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use super::postprocess::{PostPipelines, HDR_FORMAT};
+use super::skybox::SKY_FORMAT;
+use super::text::TextVertex;
 use super::types::Vertex;
 
+/// Copias de los WGSL embebidas en el binario, por nombre de archivo.
+const EMBEDDED_SHADERS: [(&str, &str); 19] = [
+    ("lighting.wgsl", include_str!("../shaders/lighting.wgsl")),
+    ("material.wgsl", include_str!("../shaders/material.wgsl")),
+    ("sky.wgsl", include_str!("../shaders/sky.wgsl")),
+    ("party_sun.wgsl", include_str!("../shaders/party_sun.wgsl")),
+    ("disco_planet.wgsl", include_str!("../shaders/disco_planet.wgsl")),
+    ("rave_planet.wgsl", include_str!("../shaders/rave_planet.wgsl")),
+    ("tropical_planet.wgsl", include_str!("../shaders/tropical_planet.wgsl")),
+    ("neon_planet.wgsl", include_str!("../shaders/neon_planet.wgsl")),
+    ("carnival_planet.wgsl", include_str!("../shaders/carnival_planet.wgsl")),
+    ("moon.wgsl", include_str!("../shaders/moon.wgsl")),
+    ("ship.wgsl", include_str!("../shaders/ship.wgsl")),
+    ("prop.wgsl", include_str!("../shaders/prop.wgsl")),
+    ("skybox.wgsl", include_str!("../shaders/skybox.wgsl")),
+    ("sky_bake.wgsl", include_str!("../shaders/sky_bake.wgsl")),
+    ("ring.wgsl", include_str!("../shaders/ring.wgsl")),
+    ("orbit.wgsl", include_str!("../shaders/orbit.wgsl")),
+    ("shadow.wgsl", include_str!("../shaders/shadow.wgsl")),
+    ("postprocess.wgsl", include_str!("../shaders/postprocess.wgsl")),
+    ("text.wgsl", include_str!("../shaders/text.wgsl")),
+];
+
+// Archivos que forman cada shader, en el orden en que se concatenan: primero los
+// compartidos (`lighting.wgsl` con la luz del grupo 1, `material.wgsl` con las
+// texturas del grupo 2, `sky.wgsl` con el cielo procedural) y al final el propio.
+
+/// Shaders de los cuerpos, en el orden de `PlanetType`.
+pub const PLANET_SHADERS: [(&str, [&str; 3]); 7] = [
+    ("Party Sun Shader", ["lighting.wgsl", "material.wgsl", "party_sun.wgsl"]),
+    ("Disco Planet Shader", ["lighting.wgsl", "material.wgsl", "disco_planet.wgsl"]),
+    ("Rave Planet Shader", ["lighting.wgsl", "material.wgsl", "rave_planet.wgsl"]),
+    ("Tropical Planet Shader", ["lighting.wgsl", "material.wgsl", "tropical_planet.wgsl"]),
+    ("Neon Planet Shader", ["lighting.wgsl", "material.wgsl", "neon_planet.wgsl"]),
+    ("Carnival Planet Shader", ["lighting.wgsl", "material.wgsl", "carnival_planet.wgsl"]),
+    ("Moon Shader", ["lighting.wgsl", "material.wgsl", "moon.wgsl"]),
+];
+pub const SHIP_SHADER: [&str; 2] = ["lighting.wgsl", "ship.wgsl"];
+pub const PROP_SHADER: [&str; 3] = ["lighting.wgsl", "material.wgsl", "prop.wgsl"];
+pub const SKYBOX_SHADER: [&str; 2] = ["sky.wgsl", "skybox.wgsl"];
+pub const SKY_BAKE_SHADER: [&str; 2] = ["sky.wgsl", "sky_bake.wgsl"];
+pub const RING_SHADER: [&str; 2] = ["lighting.wgsl", "ring.wgsl"];
+pub const ORBIT_SHADER: [&str; 1] = ["orbit.wgsl"];
+pub const SHADOW_SHADER: [&str; 1] = ["shadow.wgsl"];
+pub const POST_SHADER: [&str; 1] = ["postprocess.wgsl"];
+pub const TEXT_SHADER: [&str; 1] = ["text.wgsl"];
+
+/// Código WGSL de los shaders. Parte de las copias embebidas; con la recarga en
+/// caliente, `reload` sustituye un archivo por su versión en disco.
+#[derive(Default)]
+pub struct ShaderLibrary {
+    overrides: HashMap<String, String>,
+}
+
+impl ShaderLibrary {
+    /// Nombres de todos los archivos de shaders.
+    pub fn files() -> impl Iterator<Item = &'static str> {
+        EMBEDDED_SHADERS.iter().map(|(name, _)| *name)
+    }
+
+    /// Lee `file` de `dir`; los pipelines creados a partir de ahora usan esa
+    /// versión. Devuelve si el código cambió (false para archivos que no son
+    /// de ningún shader).
+    pub fn reload(&mut self, dir: &Path, file: &str) -> std::io::Result<bool> {
+        let Some(current) = self.get(file) else {
+            return Ok(false);
+        };
+        let source = fs::read_to_string(dir.join(file))?;
+        if source == current {
+            return Ok(false);
+        }
+        self.overrides.insert(file.to_string(), source);
+        Ok(true)
+    }
+
+    fn get(&self, file: &str) -> Option<&str> {
+        match self.overrides.get(file) {
+            Some(source) => Some(source),
+            None => EMBEDDED_SHADERS.iter().find(|(name, _)| *name == file).map(|(_, source)| *source),
+        }
+    }
+
+    /// Concatena los archivos indicados.
+    fn source(&self, files: &[&str]) -> String {
+        let mut source = String::new();
+        for file in files {
+            source.push_str(self.get(file).unwrap_or_else(|| panic!("shader desconocido: {}", file)));
+            source.push('\n');
+        }
+        source
+    }
+
+    /// Líneas que ocupa cada archivo en el código concatenado de `files`, para
+    /// ubicar los errores de compilación (ej. "lighting.wgsl 1-90, ring.wgsl 91-140").
+    pub fn line_ranges(&self, files: &[&str]) -> String {
+        let mut start = 1;
+        let mut ranges = Vec::new();
+        for file in files {
+            // `source` añade un salto de línea tras cada archivo
+            let lines = self.get(file).map_or(0, |source| source.matches('\n').count()) + 1;
+            ranges.push(format!("{} {}-{}", file, start, start + lines - 1));
+            start += lines;
+        }
+        ranges.join(", ")
+    }
+
+    fn module(&self, device: &wgpu::Device, label: &str, files: &[&str]) -> wgpu::ShaderModule {
+        device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some(label),
+            source: wgpu::ShaderSource::Wgsl(self.source(files).into()),
+        })
+    }
+}
+
 pub fn create_all_planet_pipelines(
     device: &wgpu::Device,
+    shaders: &ShaderLibrary,
     bind_group_layout: &wgpu::BindGroupLayout,
+    light_bind_group_layout: &wgpu::BindGroupLayout,
+    material_bind_group_layout: &wgpu::BindGroupLayout,
     color_format: wgpu::TextureFormat,
 ) -> Vec<wgpu::RenderPipeline> {
-    let shaders = vec![
-        device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: Some("Party Sun Shader"),
-            source: wgpu::ShaderSource::Wgsl(include_str!("../shaders/party_sun.wgsl").into()),
-        }),
-        device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: Some("Disco Planet Shader"),
-            source: wgpu::ShaderSource::Wgsl(include_str!("../shaders/disco_planet.wgsl").into()),
-        }),
-        device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: Some("Rave Planet Shader"),
-            source: wgpu::ShaderSource::Wgsl(include_str!("../shaders/rave_planet.wgsl").into()),
-        }),
-        device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: Some("Tropical Planet Shader"),
-            source: wgpu::ShaderSource::Wgsl(include_str!("../shaders/tropical_planet.wgsl").into()),
-        }),
-        device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: Some("Neon Planet Shader"),
-            source: wgpu::ShaderSource::Wgsl(include_str!("../shaders/neon_planet.wgsl").into()),
-        }),
-        device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: Some("Carnival Planet Shader"),
-            source: wgpu::ShaderSource::Wgsl(include_str!("../shaders/carnival_planet.wgsl").into()),
-        }),
-    ];
+    (0..PLANET_SHADERS.len()).map(|index| {
+        create_planet_pipeline(
+            device,
+            shaders,
+            index,
+            bind_group_layout,
+            light_bind_group_layout,
+            material_bind_group_layout,
+            color_format,
+        )
+    }).collect()
+}
+
+/// Pipeline del cuerpo de tipo `index` (ver `PLANET_SHADERS`).
+pub fn create_planet_pipeline(
+    device: &wgpu::Device,
+    shaders: &ShaderLibrary,
+    index: usize,
+    bind_group_layout: &wgpu::BindGroupLayout,
+    light_bind_group_layout: &wgpu::BindGroupLayout,
+    material_bind_group_layout: &wgpu::BindGroupLayout,
+    color_format: wgpu::TextureFormat,
+) -> wgpu::RenderPipeline {
+    let (label, files) = PLANET_SHADERS[index];
+    let shader = shaders.module(device, label, &files);
 
     let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
         label: Some("Planet Pipeline Layout"),
-        bind_group_layouts: &[bind_group_layout],
+        bind_group_layouts: &[bind_group_layout, light_bind_group_layout, material_bind_group_layout],
         push_constant_ranges: &[],
     });
 
-    shaders.iter().map(|shader| {
-        create_pipeline(device, &pipeline_layout, color_format, shader, true)
-    }).collect()
+    create_pipeline(device, &pipeline_layout, color_format, &shader, true, Some(wgpu::Face::Back))
 }
 
-pub fn create_moon_pipeline(
+pub fn create_ship_pipeline(
     device: &wgpu::Device,
+    shaders: &ShaderLibrary,
     bind_group_layout: &wgpu::BindGroupLayout,
+    light_bind_group_layout: &wgpu::BindGroupLayout,
     color_format: wgpu::TextureFormat,
 ) -> wgpu::RenderPipeline {
-    let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-        label: Some("Moon Shader"),
-        source: wgpu::ShaderSource::Wgsl(include_str!("../shaders/moon.wgsl").into()),
-    });
+    let shader = shaders.module(device, "Ship Shader", &SHIP_SHADER);
 
     let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-        label: Some("Moon Pipeline Layout"),
-        bind_group_layouts: &[bind_group_layout],
+        label: Some("Ship Pipeline Layout"),
+        bind_group_layouts: &[bind_group_layout, light_bind_group_layout],
         push_constant_ranges: &[],
     });
 
-    create_pipeline(device, &pipeline_layout, color_format, &shader, true)
+    create_pipeline(device, &pipeline_layout, color_format, &shader, true, Some(wgpu::Face::Back))
 }
 
-pub fn create_ship_pipeline(
+/// Pipeline de los modelos importados (props y nave de la escena). Sin culling:
+/// muchos modelos traen láminas de una sola cara.
+pub fn create_prop_pipeline(
     device: &wgpu::Device,
+    shaders: &ShaderLibrary,
     bind_group_layout: &wgpu::BindGroupLayout,
+    light_bind_group_layout: &wgpu::BindGroupLayout,
+    material_bind_group_layout: &wgpu::BindGroupLayout,
     color_format: wgpu::TextureFormat,
 ) -> wgpu::RenderPipeline {
-    let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-        label: Some("Ship Shader"),
-        source: wgpu::ShaderSource::Wgsl(include_str!("../shaders/ship.wgsl").into()),
-    });
+    let shader = shaders.module(device, "Prop Shader", &PROP_SHADER);
 
     let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-        label: Some("Ship Pipeline Layout"),
-        bind_group_layouts: &[bind_group_layout],
+        label: Some("Prop Pipeline Layout"),
+        bind_group_layouts: &[bind_group_layout, light_bind_group_layout, material_bind_group_layout],
         push_constant_ranges: &[],
     });
 
-    create_pipeline(device, &pipeline_layout, color_format, &shader, true)
+    create_pipeline(device, &pipeline_layout, color_format, &shader, true, None)
 }
 
+/// Pipeline del fondo. Con `sky_bind_group_layout` muestrea el cubemap del cielo
+/// (grupo 1); sin él calcula el cielo procedural en cada frame.
 pub fn create_skybox_pipeline(
     device: &wgpu::Device,
+    shaders: &ShaderLibrary,
     bind_group_layout: &wgpu::BindGroupLayout,
+    sky_bind_group_layout: Option<&wgpu::BindGroupLayout>,
     color_format: wgpu::TextureFormat,
 ) -> wgpu::RenderPipeline {
-    let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-        label: Some("Skybox Shader"),
-        source: wgpu::ShaderSource::Wgsl(include_str!("../shaders/skybox.wgsl").into()),
-    });
+    let shader = shaders.module(device, "Skybox Shader", &SKYBOX_SHADER);
 
+    let (bind_group_layouts, fragment_entry) = match sky_bind_group_layout {
+        Some(sky_bind_group_layout) => (vec![bind_group_layout, sky_bind_group_layout], "fs_cubemap"),
+        None => (vec![bind_group_layout], "fs_main"),
+    };
     let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
         label: Some("Skybox Pipeline Layout"),
-        bind_group_layouts: &[bind_group_layout],
+        bind_group_layouts: &bind_group_layouts,
         push_constant_ranges: &[],
     });
 
@@ -109,7 +236,7 @@ pub fn create_skybox_pipeline(
         },
         fragment: Some(wgpu::FragmentState {
             module: &shader,
-            entry_point: "fs_main",
+            entry_point: fragment_entry,
             targets: &[Some(wgpu::ColorTargetState {
                 format: color_format,
                 blend: Some(wgpu::BlendState::REPLACE),
@@ -144,17 +271,16 @@ pub fn create_skybox_pipeline(
 
 pub fn create_ring_pipeline(
     device: &wgpu::Device,
+    shaders: &ShaderLibrary,
     bind_group_layout: &wgpu::BindGroupLayout,
+    light_bind_group_layout: &wgpu::BindGroupLayout,
     color_format: wgpu::TextureFormat,
 ) -> wgpu::RenderPipeline {
-    let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-        label: Some("Ring Shader"),
-        source: wgpu::ShaderSource::Wgsl(include_str!("../shaders/ring.wgsl").into()),
-    });
+    let shader = shaders.module(device, "Ring Shader", &RING_SHADER);
 
     let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
         label: Some("Ring Pipeline Layout"),
-        bind_group_layouts: &[bind_group_layout],
+        bind_group_layouts: &[bind_group_layout, light_bind_group_layout],
         push_constant_ranges: &[],
     });
 
@@ -205,13 +331,11 @@ pub fn create_ring_pipeline(
 
 pub fn create_orbit_pipeline(
     device: &wgpu::Device,
+    shaders: &ShaderLibrary,
     bind_group_layout: &wgpu::BindGroupLayout,
     color_format: wgpu::TextureFormat,
 ) -> wgpu::RenderPipeline {
-    let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-        label: Some("Orbit Shader"),
-        source: wgpu::ShaderSource::Wgsl(include_str!("../shaders/orbit.wgsl").into()),
-    });
+    let shader = shaders.module(device, "Orbit Shader", &ORBIT_SHADER);
 
     let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
         label: Some("Orbit Pipeline Layout"),
@@ -264,12 +388,206 @@ pub fn create_orbit_pipeline(
     })
 }
 
+/// Pipeline que genera una cara del cubemap del cielo (`fs_starfield` o
+/// `fs_panorama` de `sky_bake.wgsl`).
+pub fn create_sky_bake_pipeline(
+    device: &wgpu::Device,
+    shaders: &ShaderLibrary,
+    bind_group_layout: &wgpu::BindGroupLayout,
+    fragment_entry: &str,
+) -> wgpu::RenderPipeline {
+    let shader = shaders.module(device, "Sky Bake Shader", &SKY_BAKE_SHADER);
+
+    let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some("Sky Bake Pipeline Layout"),
+        bind_group_layouts: &[bind_group_layout],
+        push_constant_ranges: &[],
+    });
+
+    create_fullscreen_pipeline(device, &layout, &shader, fragment_entry, SKY_FORMAT, None)
+}
+
+/// Pipeline de la pasada de sombras: solo profundidad, el fragment shader escribe
+/// la distancia lineal al sol en `frag_depth`.
+pub fn create_shadow_pipeline(
+    device: &wgpu::Device,
+    shaders: &ShaderLibrary,
+    bind_group_layout: &wgpu::BindGroupLayout,
+    face_bind_group_layout: &wgpu::BindGroupLayout,
+) -> wgpu::RenderPipeline {
+    let shader = shaders.module(device, "Shadow Shader", &SHADOW_SHADER);
+
+    let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some("Shadow Pipeline Layout"),
+        bind_group_layouts: &[bind_group_layout, face_bind_group_layout],
+        push_constant_ranges: &[],
+    });
+
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("Shadow Pipeline"),
+        layout: Some(&pipeline_layout),
+        cache: None,
+        vertex: wgpu::VertexState {
+            module: &shader,
+            entry_point: "vs_main",
+            buffers: &[Vertex::desc()],
+            compilation_options: Default::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: &shader,
+            entry_point: "fs_main",
+            targets: &[],
+            compilation_options: Default::default(),
+        }),
+        primitive: wgpu::PrimitiveState {
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            strip_index_format: None,
+            front_face: wgpu::FrontFace::Ccw,
+            cull_mode: None,
+            polygon_mode: wgpu::PolygonMode::Fill,
+            unclipped_depth: false,
+            conservative: false,
+        },
+        depth_stencil: Some(wgpu::DepthStencilState {
+            format: wgpu::TextureFormat::Depth32Float,
+            depth_write_enabled: true,
+            depth_compare: wgpu::CompareFunction::Less,
+            stencil: wgpu::StencilState::default(),
+            bias: wgpu::DepthBiasState::default(),
+        }),
+        multisample: wgpu::MultisampleState {
+            count: 1,
+            mask: !0,
+            alpha_to_coverage_enabled: false,
+        },
+        multiview: None,
+    })
+}
+
+/// Pipeline del HUD: quads 2D en píxeles con mezcla alfa, sin profundidad,
+/// dibujados sobre el frame ya tonemapeado.
+pub fn create_text_pipeline(
+    device: &wgpu::Device,
+    shaders: &ShaderLibrary,
+    bind_group_layout: &wgpu::BindGroupLayout,
+    output_format: wgpu::TextureFormat,
+) -> wgpu::RenderPipeline {
+    let shader = shaders.module(device, "Text Shader", &TEXT_SHADER);
+
+    let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some("Text Pipeline Layout"),
+        bind_group_layouts: &[bind_group_layout],
+        push_constant_ranges: &[],
+    });
+
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("Text Pipeline"),
+        layout: Some(&pipeline_layout),
+        cache: None,
+        vertex: wgpu::VertexState {
+            module: &shader,
+            entry_point: "vs_main",
+            buffers: &[TextVertex::desc()],
+            compilation_options: Default::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: &shader,
+            entry_point: "fs_main",
+            targets: &[Some(wgpu::ColorTargetState {
+                format: output_format,
+                blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+            compilation_options: Default::default(),
+        }),
+        primitive: wgpu::PrimitiveState::default(),
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState::default(),
+        multiview: None,
+    })
+}
+
+/// Pipelines de pantalla completa del postproceso. Todo el bloom trabaja en
+/// `HDR_FORMAT`; solo la composición escribe en el formato de salida.
+pub fn create_post_pipelines(
+    device: &wgpu::Device,
+    shaders: &ShaderLibrary,
+    bind_group_layout: &wgpu::BindGroupLayout,
+    composite_bind_group_layout: &wgpu::BindGroupLayout,
+    output_format: wgpu::TextureFormat,
+) -> PostPipelines {
+    let shader = shaders.module(device, "Post Process Shader", &POST_SHADER);
+
+    let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some("Post Pipeline Layout"),
+        bind_group_layouts: &[bind_group_layout],
+        push_constant_ranges: &[],
+    });
+    let composite_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some("Post Composite Pipeline Layout"),
+        bind_group_layouts: &[composite_bind_group_layout],
+        push_constant_ranges: &[],
+    });
+
+    let additive = wgpu::BlendState {
+        color: wgpu::BlendComponent {
+            src_factor: wgpu::BlendFactor::One,
+            dst_factor: wgpu::BlendFactor::One,
+            operation: wgpu::BlendOperation::Add,
+        },
+        alpha: wgpu::BlendComponent::REPLACE,
+    };
+
+    PostPipelines {
+        prefilter: create_fullscreen_pipeline(device, &layout, &shader, "fs_prefilter", HDR_FORMAT, None),
+        downsample: create_fullscreen_pipeline(device, &layout, &shader, "fs_downsample", HDR_FORMAT, None),
+        upsample: create_fullscreen_pipeline(device, &layout, &shader, "fs_upsample", HDR_FORMAT, Some(additive)),
+        composite: create_fullscreen_pipeline(device, &composite_layout, &shader, "fs_composite", output_format, None),
+    }
+}
+
+fn create_fullscreen_pipeline(
+    device: &wgpu::Device,
+    layout: &wgpu::PipelineLayout,
+    shader: &wgpu::ShaderModule,
+    fragment_entry: &str,
+    format: wgpu::TextureFormat,
+    blend: Option<wgpu::BlendState>,
+) -> wgpu::RenderPipeline {
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some(fragment_entry),
+        layout: Some(layout),
+        cache: None,
+        vertex: wgpu::VertexState {
+            module: shader,
+            entry_point: "vs_fullscreen",
+            buffers: &[],
+            compilation_options: Default::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: shader,
+            entry_point: fragment_entry,
+            targets: &[Some(wgpu::ColorTargetState {
+                format,
+                blend,
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+            compilation_options: Default::default(),
+        }),
+        primitive: wgpu::PrimitiveState::default(),
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState::default(),
+        multiview: None,
+    })
+}
+
 fn create_pipeline(
     device: &wgpu::Device,
     layout: &wgpu::PipelineLayout,
     color_format: wgpu::TextureFormat,
     shader: &wgpu::ShaderModule,
     depth_write: bool,
+    cull_mode: Option<wgpu::Face>,
 ) -> wgpu::RenderPipeline {
     device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
         label: Some("Render Pipeline"),
@@ -295,7 +613,7 @@ fn create_pipeline(
             topology: wgpu::PrimitiveTopology::TriangleList,
             strip_index_format: None,
             front_face: wgpu::FrontFace::Ccw,
-            cull_mode: Some(wgpu::Face::Back),
+            cull_mode,
             polygon_mode: wgpu::PolygonMode::Fill,
             unclipped_depth: false,
             conservative: false,
@@ -1,6 +1,19 @@
-use glam::{Mat4, Vec3};
-
-#[derive(Debug, Clone, Copy, PartialEq)]
+use glam::{Mat3, Mat4, Vec3};
+use serde::Deserialize;
+
+use super::material::MaterialSettings;
+use super::orbit::OrbitalElements;
+
+/// Radio de la esfera base creada en `RendererState` (`create_sphere(2.0, ..)`).
+/// El radio real de un cuerpo es `BASE_SPHERE_RADIUS * scale`.
+pub const BASE_SPHERE_RADIUS: f32 = 2.0;
+/// Radios interior y exterior de la malla de anillos (`create_ring(1.0, 2.0, ..)`).
+pub const RING_MESH_RADII: (f32, f32) = (1.0, 2.0);
+/// Los anillos se escalan a `RING_SCALE * scale` y se inclinan `RING_TILT` radianes sobre X.
+const RING_SCALE: f32 = 1.8;
+const RING_TILT: f32 = 0.3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
 pub enum PlanetType {
     Sun,           // Sol de la fiesta
     Disco,         // Planeta discoteca
@@ -8,102 +21,90 @@ pub enum PlanetType {
     Tropical,      // Fiesta tropical
     Neon,          // Fiesta neón
     Carnival,      // Carnaval
+    Moon,          // Luna lavanda
 }
 
+/// Cuerpo del sistema tal como se describe en el archivo de escena.
+/// Los campos opcionales (órbita, anillos, lunas) valen None/0/false/vacío si se omiten;
+/// un cuerpo sin `orbit` queda fijo en el origen de su padre.
+///
+/// Cada cuerpo orbita alrededor de su padre (o del origen si no tiene). Al cargar la
+/// escena el árbol se aplana en una lista donde cada padre aparece antes que sus hijos
+/// y `parent` guarda el índice del padre en esa lista.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct Planet {
+    pub name: String,
     pub planet_type: PlanetType,
-    pub orbit_radius: f32,
-    pub orbit_speed: f32,
+    #[serde(default)]
+    pub orbit: Option<OrbitalElements>,
+    #[serde(default)]
     pub rotation_speed: f32,
     pub scale: f32,
-    pub orbit_inclination: f32,
-    pub initial_angle: f32,
+    /// Parámetro gravitacional (G·M) en el modo gravedad; si se omite se deduce
+    /// de las órbitas de sus lunas o de su tamaño (ver `gravity::NBody`)
+    #[serde(default)]
+    pub mass: Option<f32>,
+    /// Texturas que se mezclan con el shader procedural del cuerpo
+    #[serde(default)]
+    pub material: Option<MaterialSettings>,
+    #[serde(default)]
     pub has_rings: bool,
-    pub has_moon: bool,
-    pub moon_orbit_radius: f32,
-    pub moon_orbit_speed: f32,
-    current_angle: f32,
+    #[serde(default)]
+    pub moons: Vec<Planet>,
+    #[serde(skip)]
+    pub parent: Option<usize>,
+    #[serde(skip)]
+    parent_frame: Mat4,
+    #[serde(skip)]
+    orbit_offset: Vec3,
+    #[serde(skip)]
     current_rotation: f32,
-    moon_angle: f32,
 }
 
 impl Planet {
-    pub fn update(&mut self, time: f32) {
-        self.current_angle = self.initial_angle + time * self.orbit_speed;
+    pub fn update(&mut self, time: f32, parent_frame: Mat4) {
+        self.orbit_offset = match &self.orbit {
+            Some(orbit) => orbit.position_at(time),
+            None => Vec3::ZERO,
+        };
         self.current_rotation = time * self.rotation_speed;
-        if self.has_moon {
-            self.moon_angle = time * self.moon_orbit_speed;
-        }
+        self.parent_frame = parent_frame;
     }
 
-    pub fn get_model_matrix(&self) -> Mat4 {
-        let position = if self.orbit_radius > 0.0 {
-            Vec3::new(
-                self.current_angle.cos() * self.orbit_radius,
-                self.current_angle.sin() * self.orbit_radius * self.orbit_inclination,
-                self.current_angle.sin() * self.orbit_radius,
-            )
-        } else {
-            Vec3::ZERO
-        };
-
-        Mat4::from_translation(position)
-            * Mat4::from_rotation_y(self.current_rotation)
-            * Mat4::from_scale(Vec3::splat(self.scale))
+    /// Coloca el cuerpo en una posición del mundo calculada fuera de su órbita
+    /// (modo gravedad). El marco del padre se queda solo con su posición, así
+    /// que la órbita dibujada sigue centrada en él.
+    pub fn place(&mut self, position: Vec3, parent_position: Option<Vec3>, time: f32) {
+        let parent_position = parent_position.unwrap_or(Vec3::ZERO);
+        self.parent_frame = Mat4::from_translation(parent_position);
+        self.orbit_offset = position - parent_position;
+        self.current_rotation = time * self.rotation_speed;
     }
 
-    pub fn get_position(&self) -> Vec3 {
-        if self.orbit_radius > 0.0 {
-            Vec3::new(
-                self.current_angle.cos() * self.orbit_radius,
-                self.current_angle.sin() * self.orbit_radius * self.orbit_inclination,
-                self.current_angle.sin() * self.orbit_radius,
-            )
-        } else {
-            Vec3::ZERO
-        }
+    pub fn radius(&self) -> f32 {
+        BASE_SPHERE_RADIUS * self.scale
     }
 
-    pub fn get_moon_position(&self) -> Option<Vec3> {
-        if !self.has_moon {
-            return None;
-        }
-
-        let planet_pos = self.get_position();
-        let moon_offset = Vec3::new(
-            self.moon_angle.cos() * self.moon_orbit_radius,
-            self.moon_angle.sin() * self.moon_orbit_radius * 0.3,
-            self.moon_angle.sin() * self.moon_orbit_radius,
-        );
-
-        // Evitar que la luna intersecte el planeta: asegurar distancia mínima
-        // Basado en la esfera base usada para los planetas (radio base = 2.0)
-        let base_sphere_radius = 2.0;
-        let planet_radius = base_sphere_radius * self.scale;
-        let moon_radius = base_sphere_radius * self.scale * 0.3; // la luna usa scale * 0.3
-        let min_dist = planet_radius + moon_radius + 0.05; // pequeño margen
+    /// Marco de referencia del padre. Solo se hereda la posición: la rotación
+    /// propia y la escala de un cuerpo no afectan a las órbitas de sus hijos.
+    pub fn get_parent_matrix(&self) -> Mat4 {
+        self.parent_frame
+    }
 
-        let mut offset = moon_offset;
-        let cur_dist = offset.length();
-        if cur_dist < min_dist {
-            // empujar la luna hacia afuera para evitar intersección
-            if cur_dist == 0.0 {
-                // en caso raro de distancia cero, colocar en eje X
-                offset = Vec3::new(min_dist, 0.0, 0.0);
-            } else {
-                offset = offset.normalize() * min_dist;
-            }
-        }
+    /// Marco de referencia del cuerpo (padre * traslación orbital), sin rotación ni escala.
+    pub fn get_frame_matrix(&self) -> Mat4 {
+        self.parent_frame * Mat4::from_translation(self.orbit_offset)
+    }
 
-        Some(planet_pos + offset)
+    pub fn get_model_matrix(&self) -> Mat4 {
+        self.get_frame_matrix()
+            * Mat4::from_rotation_y(self.current_rotation)
+            * Mat4::from_scale(Vec3::splat(self.scale))
     }
 
-    pub fn get_moon_model_matrix(&self) -> Option<Mat4> {
-        self.get_moon_position().map(|pos| {
-            Mat4::from_translation(pos)
-                * Mat4::from_rotation_y(self.current_rotation * 2.0)
-                * Mat4::from_scale(Vec3::splat(self.scale * 0.3))
-        })
+    pub fn get_position(&self) -> Vec3 {
+        self.get_frame_matrix().transform_point3(Vec3::ZERO)
     }
 
     pub fn get_rings_model_matrix(&self) -> Option<Mat4> {
@@ -111,174 +112,82 @@ impl Planet {
             return None;
         }
 
-        let position = self.get_position();
         Some(
-            Mat4::from_translation(position)
-                * Mat4::from_rotation_x(0.3)
+            self.get_frame_matrix()
+                * Mat4::from_rotation_x(RING_TILT)
                 * Mat4::from_rotation_y(self.current_rotation * 0.5)
-                * Mat4::from_scale(Vec3::new(self.scale * 1.8, 0.1, self.scale * 1.8))
+                * Mat4::from_scale(Vec3::new(self.scale * RING_SCALE, 0.1, self.scale * RING_SCALE))
         )
     }
-}
 
-pub fn create_planet_system() -> Vec<Planet> {
-    use std::f32::consts::PI;
-    
-    let mut planets = vec![
-        // Sol de la Fiesta - Centro de todo
-        Planet {
-            planet_type: PlanetType::Sun,
-            orbit_radius: 0.0,
-            orbit_speed: 0.0,
-            rotation_speed: 0.3,
-            scale: 4.0,
-            orbit_inclination: 0.0,
-            initial_angle: 0.0,
-            has_rings: false,
-            has_moon: false,
-            moon_orbit_radius: 0.0,
-            moon_orbit_speed: 0.0,
-            current_angle: 0.0,
-            current_rotation: 0.0,
-            moon_angle: 0.0,
-        },
-        // Planeta Disco - con luna
-        Planet {
-            planet_type: PlanetType::Disco,
-            orbit_radius: 12.0,
-            orbit_speed: 1.8,
-            rotation_speed: 1.5,
-            scale: 1.2,
-            orbit_inclination: 0.02,
-            initial_angle: 0.0,
-            has_rings: false,
-            has_moon: true,
-            moon_orbit_radius: 2.5,
-            moon_orbit_speed: 3.0,
-            current_angle: 0.0,
-            current_rotation: 0.0,
-            moon_angle: 0.0,
-        },
-        // Planeta Rave - con anillos brillantes
-        Planet {
-            planet_type: PlanetType::Rave,
-            orbit_radius: 18.0,
-            orbit_speed: 1.3,
-            rotation_speed: 2.0,
-            scale: 1.5,
-            orbit_inclination: 0.04,
-            initial_angle: PI * 0.5,
-            has_rings: true,
-            has_moon: false,
-            moon_orbit_radius: 0.0,
-            moon_orbit_speed: 0.0,
-            current_angle: PI * 0.5,
-            current_rotation: 0.0,
-            moon_angle: 0.0,
-        },
-        // Planeta Tropical - sin extras
-        Planet {
-            planet_type: PlanetType::Tropical,
-            orbit_radius: 26.0,
-            orbit_speed: 0.9,
-            rotation_speed: 1.2,
-            scale: 1.8,
-            orbit_inclination: 0.03,
-            initial_angle: PI,
-            has_rings: false,
-            has_moon: false,
-            moon_orbit_radius: 0.0,
-            moon_orbit_speed: 0.0,
-            current_angle: PI,
-            current_rotation: 0.0,
-            moon_angle: 0.0,
-        },
-        // Planeta Neón - con anillos y luna
-        Planet {
-            planet_type: PlanetType::Neon,
-            orbit_radius: 35.0,
-            orbit_speed: 0.6,
-            rotation_speed: 0.8,
-            scale: 2.2,
-            orbit_inclination: 0.05,
-            initial_angle: PI * 1.3,
-            has_rings: true,
-            has_moon: true,
-            moon_orbit_radius: 4.0,
-            moon_orbit_speed: 2.5,
-            current_angle: PI * 1.3,
-            current_rotation: 0.0,
-            moon_angle: 0.0,
-        },
-        // Planeta Carnaval - con luna
-        Planet {
-            planet_type: PlanetType::Carnival,
-            orbit_radius: 45.0,
-            orbit_speed: 0.4,
-            rotation_speed: 0.6,
-            scale: 2.5,
-            orbit_inclination: 0.06,
-            initial_angle: PI * 1.8,
-            has_rings: false,
-            has_moon: true,
-            moon_orbit_radius: 4.5,
-            moon_orbit_speed: 2.0,
-            current_angle: PI * 1.8,
-            current_rotation: 0.0,
-            moon_angle: 0.0,
-        },
-    ];
+    /// Anillos en el mundo: normal de su plano y radios interior y exterior
+    /// (centrados en el cuerpo). Coincide con `get_rings_model_matrix`.
+    pub fn ring_annulus(&self) -> Option<(Vec3, f32, f32)> {
+        if !self.has_rings {
+            return None;
+        }
+        let (inner, outer) = RING_MESH_RADII;
+        let scale = self.scale * RING_SCALE;
+        Some((Mat3::from_rotation_x(RING_TILT) * Vec3::Y, inner * scale, outer * scale))
+    }
+}
 
-    // Reducir a la mitad la velocidad orbital de todos los planetas
-    for p in &mut planets {
-        p.orbit_speed *= 0.5;
+/// Actualiza todos los cuerpos en orden, de modo que cada hijo use el marco ya
+/// actualizado de su padre.
+pub fn update_system(planets: &mut [Planet], time: f32) {
+    for i in 0..planets.len() {
+        let parent_frame = planets[i].parent
+            .map(|parent| planets[parent].get_frame_matrix())
+            .unwrap_or(Mat4::IDENTITY);
+        planets[i].update(time, parent_frame);
     }
+}
 
+/// Aplana el árbol de cuerpos de la escena (padres antes que hijos) y asigna `parent`.
+pub fn flatten_system(bodies: Vec<Planet>) -> Vec<Planet> {
+    let mut planets = Vec::new();
+    for body in bodies {
+        push_body(&mut planets, body, None);
+    }
     planets
 }
 
+fn push_body(planets: &mut Vec<Planet>, mut body: Planet, parent: Option<usize>) {
+    let moons = std::mem::take(&mut body.moons);
+    body.parent = parent;
+
+    let index = planets.len();
+    planets.push(body);
+    for moon in moons {
+        push_body(planets, moon, Some(index));
+    }
+}
+
+/// Destino de warp. Si tiene `body`, `position` y `target` se miden desde el
+/// centro de ese cuerpo, así que el destino lo sigue mientras orbita.
+#[derive(Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct WarpPoint {
-    pub name: &'static str,
+    pub name: String,
+    #[serde(default)]
+    pub body: Option<String>,
     pub position: Vec3,
+    #[serde(default)]
     pub target: Vec3,
+    /// Índice de `body` en la lista aplanada (se resuelve al cargar la escena)
+    #[serde(skip)]
+    pub body_index: Option<usize>,
 }
 
-pub fn get_warp_points() -> Vec<WarpPoint> {
-    vec![
-        WarpPoint {
-            name: "Vista General",
-            position: Vec3::new(0.0, 25.0, 60.0),
-            target: Vec3::ZERO,
-        },
-        WarpPoint {
-            name: "Sol de Fiesta",
-            position: Vec3::new(0.0, 8.0, 12.0),
-            target: Vec3::ZERO,
-        },
-        WarpPoint {
-            name: "Planeta Disco",
-            position: Vec3::new(12.0, 5.0, 5.0),
-            target: Vec3::new(12.0, 0.0, 0.0),
-        },
-        WarpPoint {
-            name: "Planeta Rave",
-            position: Vec3::new(18.0, 8.0, 8.0),
-            target: Vec3::new(18.0, 0.0, 0.0),
-        },
-        WarpPoint {
-            name: "Planeta Tropical",
-            position: Vec3::new(26.0, 10.0, 10.0),
-            target: Vec3::new(26.0, 0.0, 0.0),
-        },
-        WarpPoint {
-            name: "Planeta Neón",
-            position: Vec3::new(35.0, 12.0, 12.0),
-            target: Vec3::new(35.0, 0.0, 0.0),
-        },
-        WarpPoint {
-            name: "Planeta Carnaval",
-            position: Vec3::new(45.0, 15.0, 15.0),
-            target: Vec3::new(45.0, 0.0, 0.0),
-        },
-    ]
-}
\ No newline at end of file
+impl WarpPoint {
+    /// Posición y punto de mira actuales en coordenadas del mundo.
+    pub fn resolve(&self, planets: &[Planet]) -> (Vec3, Vec3) {
+        match self.body_index {
+            Some(index) => {
+                let frame = planets[index].get_frame_matrix();
+                (frame.transform_point3(self.position), frame.transform_point3(self.target))
+            }
+            None => (self.position, self.target),
+        }
+    }
+}
@@ -0,0 +1,429 @@
+use bytemuck::{Pod, Zeroable};
+use std::str::FromStr;
+use wgpu::util::DeviceExt;
+
+use super::pipelines::ShaderLibrary;
+
+/// Formato del render target intermedio en el que se dibuja toda la escena.
+pub const HDR_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba16Float;
+/// Niveles de la cadena de bloom (el primero a media resolución).
+const BLOOM_LEVELS: usize = 5;
+const BLOOM_STRENGTH: f32 = 0.6;
+/// Brillo a partir del cual un píxel contribuye al bloom.
+const BLOOM_THRESHOLD: f32 = 1.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tonemapper {
+    Aces,
+    Reinhard,
+    AgX,
+}
+
+impl Tonemapper {
+    pub fn next(self) -> Self {
+        match self {
+            Tonemapper::Aces => Tonemapper::Reinhard,
+            Tonemapper::Reinhard => Tonemapper::AgX,
+            Tonemapper::AgX => Tonemapper::Aces,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Tonemapper::Aces => "ACES",
+            Tonemapper::Reinhard => "Reinhard",
+            Tonemapper::AgX => "AgX",
+        }
+    }
+}
+
+impl FromStr for Tonemapper {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "aces" => Ok(Tonemapper::Aces),
+            "reinhard" => Ok(Tonemapper::Reinhard),
+            "agx" => Ok(Tonemapper::AgX),
+            _ => Err(format!("tonemapper desconocido `{}` (aces, reinhard, agx)", value)),
+        }
+    }
+}
+
+/// Ajustes del postproceso que se pueden cambiar en tiempo real.
+#[derive(Debug, Clone, Copy)]
+pub struct PostSettings {
+    pub tonemapper: Tonemapper,
+    pub exposure: f32,
+    pub bloom: bool,
+}
+
+impl Default for PostSettings {
+    fn default() -> Self {
+        Self {
+            tonemapper: Tonemapper::Aces,
+            exposure: 1.0,
+            bloom: true,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Pod, Zeroable)]
+struct PostUniforms {
+    exposure: f32,
+    bloom_strength: f32,
+    bloom_threshold: f32,
+    tonemapper: u32,
+    encode_srgb: u32,
+    _padding: [u32; 3],
+}
+
+pub struct PostPipelines {
+    pub prefilter: wgpu::RenderPipeline,
+    pub downsample: wgpu::RenderPipeline,
+    pub upsample: wgpu::RenderPipeline,
+    pub composite: wgpu::RenderPipeline,
+}
+
+/// Render target HDR de la escena, cadena de bloom y composición final.
+///
+/// Cada frame: la escena se dibuja en `hdr_view`; `run` filtra lo que supera el
+/// umbral, lo reduce por la cadena de niveles, lo vuelve a subir sumando cada
+/// nivel y finalmente aplica exposición y tonemapping sobre el destino.
+pub struct PostProcess {
+    sampler: wgpu::Sampler,
+    uniform_buffer: wgpu::Buffer,
+    bind_group_layout: wgpu::BindGroupLayout,
+    composite_bind_group_layout: wgpu::BindGroupLayout,
+    pipelines: PostPipelines,
+    targets: PostTargets,
+    output_format: wgpu::TextureFormat,
+    encode_srgb: bool,
+}
+
+/// Texturas que dependen del tamaño de la ventana y sus bind groups.
+struct PostTargets {
+    hdr_view: wgpu::TextureView,
+    bloom_views: Vec<wgpu::TextureView>,
+    prefilter_bind_group: wgpu::BindGroup,
+    // downsample[i] lee el nivel i y escribe el i + 1; upsample[i] lee el i + 1 y suma al i
+    downsample_bind_groups: Vec<wgpu::BindGroup>,
+    upsample_bind_groups: Vec<wgpu::BindGroup>,
+    composite_bind_group: wgpu::BindGroup,
+}
+
+impl PostProcess {
+    pub fn new(
+        device: &wgpu::Device,
+        shaders: &ShaderLibrary,
+        width: u32,
+        height: u32,
+        output_format: wgpu::TextureFormat,
+    ) -> Self {
+        let texture_entry = |binding| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Texture {
+                sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                view_dimension: wgpu::TextureViewDimension::D2,
+                multisampled: false,
+            },
+            count: None,
+        };
+        let sampler_entry = wgpu::BindGroupLayoutEntry {
+            binding: 1,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+            count: None,
+        };
+        let uniform_entry = wgpu::BindGroupLayoutEntry {
+            binding: 2,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[texture_entry(0), sampler_entry, uniform_entry],
+            label: Some("post_bind_group_layout"),
+        });
+        let composite_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[texture_entry(0), sampler_entry, uniform_entry, texture_entry(3)],
+            label: Some("post_composite_bind_group_layout"),
+        });
+
+        let pipelines = super::pipelines::create_post_pipelines(
+            device,
+            shaders,
+            &bind_group_layout,
+            &composite_bind_group_layout,
+            output_format,
+        );
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Post Sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let encode_srgb = !output_format.is_srgb();
+        let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Post Uniform Buffer"),
+            contents: bytemuck::cast_slice(&[Self::uniforms(&PostSettings::default(), encode_srgb)]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let targets = PostTargets::new(
+            device,
+            width,
+            height,
+            &bind_group_layout,
+            &composite_bind_group_layout,
+            &sampler,
+            &uniform_buffer,
+        );
+
+        Self {
+            sampler,
+            uniform_buffer,
+            bind_group_layout,
+            composite_bind_group_layout,
+            pipelines,
+            targets,
+            output_format,
+            encode_srgb,
+        }
+    }
+
+    /// Vista del target HDR en el que se dibuja la escena.
+    pub fn hdr_view(&self) -> &wgpu::TextureView {
+        &self.targets.hdr_view
+    }
+
+    /// Recrea los pipelines con el código actual de `shaders` (recarga en caliente).
+    pub fn create_pipelines(&self, device: &wgpu::Device, shaders: &ShaderLibrary) -> PostPipelines {
+        super::pipelines::create_post_pipelines(
+            device,
+            shaders,
+            &self.bind_group_layout,
+            &self.composite_bind_group_layout,
+            self.output_format,
+        )
+    }
+
+    pub fn set_pipelines(&mut self, pipelines: PostPipelines) {
+        self.pipelines = pipelines;
+    }
+
+    /// Recrea el target HDR y la cadena de bloom para el nuevo tamaño.
+    pub fn resize(&mut self, device: &wgpu::Device, width: u32, height: u32) {
+        self.targets = PostTargets::new(
+            device,
+            width,
+            height,
+            &self.bind_group_layout,
+            &self.composite_bind_group_layout,
+            &self.sampler,
+            &self.uniform_buffer,
+        );
+    }
+
+    pub fn update(&self, queue: &wgpu::Queue, settings: &PostSettings) {
+        let uniforms = Self::uniforms(settings, self.encode_srgb);
+        queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[uniforms]));
+    }
+
+    /// Aplica bloom y tonemapping del target HDR sobre `output`.
+    pub fn run(&self, encoder: &mut wgpu::CommandEncoder, output: &wgpu::TextureView, bloom: bool) {
+        let targets = &self.targets;
+
+        if bloom {
+            fullscreen_pass(
+                encoder,
+                "Bloom Prefilter Pass",
+                &targets.bloom_views[0],
+                wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                Some((&self.pipelines.prefilter, &targets.prefilter_bind_group)),
+            );
+
+            for level in 1..BLOOM_LEVELS {
+                fullscreen_pass(
+                    encoder,
+                    "Bloom Downsample Pass",
+                    &targets.bloom_views[level],
+                    wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                    Some((&self.pipelines.downsample, &targets.downsample_bind_groups[level - 1])),
+                );
+            }
+
+            for level in (0..BLOOM_LEVELS - 1).rev() {
+                fullscreen_pass(
+                    encoder,
+                    "Bloom Upsample Pass",
+                    &targets.bloom_views[level],
+                    wgpu::LoadOp::Load,
+                    Some((&self.pipelines.upsample, &targets.upsample_bind_groups[level])),
+                );
+            }
+        } else {
+            // Sin bloom se limpia el primer nivel para que la composición no sume nada
+            fullscreen_pass(
+                encoder,
+                "Bloom Clear Pass",
+                &targets.bloom_views[0],
+                wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                None,
+            );
+        }
+
+        fullscreen_pass(
+            encoder,
+            "Tonemap Pass",
+            output,
+            wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+            Some((&self.pipelines.composite, &targets.composite_bind_group)),
+        );
+    }
+
+    fn uniforms(settings: &PostSettings, encode_srgb: bool) -> PostUniforms {
+        PostUniforms {
+            exposure: settings.exposure,
+            bloom_strength: BLOOM_STRENGTH,
+            bloom_threshold: BLOOM_THRESHOLD,
+            tonemapper: settings.tonemapper as u32,
+            encode_srgb: encode_srgb as u32,
+            _padding: [0; 3],
+        }
+    }
+}
+
+impl PostTargets {
+    fn new(
+        device: &wgpu::Device,
+        width: u32,
+        height: u32,
+        bind_group_layout: &wgpu::BindGroupLayout,
+        composite_bind_group_layout: &wgpu::BindGroupLayout,
+        sampler: &wgpu::Sampler,
+        uniform_buffer: &wgpu::Buffer,
+    ) -> Self {
+        let create_view = |label: &str, width: u32, height: u32| {
+            device.create_texture(&wgpu::TextureDescriptor {
+                label: Some(label),
+                size: wgpu::Extent3d {
+                    width: width.max(1),
+                    height: height.max(1),
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: HDR_FORMAT,
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+                view_formats: &[],
+            })
+            .create_view(&wgpu::TextureViewDescriptor::default())
+        };
+
+        let hdr_view = create_view("HDR Texture", width, height);
+        let bloom_views: Vec<_> = (0..BLOOM_LEVELS)
+            .map(|level| create_view(&format!("Bloom Texture {}", level), width >> (level + 1), height >> (level + 1)))
+            .collect();
+
+        let create_bind_group = |source: &wgpu::TextureView| {
+            device.create_bind_group(&wgpu::BindGroupDescriptor {
+                layout: bind_group_layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: wgpu::BindingResource::TextureView(source),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: wgpu::BindingResource::Sampler(sampler),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 2,
+                        resource: uniform_buffer.as_entire_binding(),
+                    },
+                ],
+                label: Some("post_bind_group"),
+            })
+        };
+
+        let prefilter_bind_group = create_bind_group(&hdr_view);
+        let downsample_bind_groups = bloom_views[..BLOOM_LEVELS - 1].iter().map(create_bind_group).collect();
+        let upsample_bind_groups = bloom_views[1..].iter().map(create_bind_group).collect();
+
+        let composite_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: composite_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&hdr_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(sampler),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: uniform_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: wgpu::BindingResource::TextureView(&bloom_views[0]),
+                },
+            ],
+            label: Some("post_composite_bind_group"),
+        });
+
+        Self {
+            hdr_view,
+            bloom_views,
+            prefilter_bind_group,
+            downsample_bind_groups,
+            upsample_bind_groups,
+            composite_bind_group,
+        }
+    }
+}
+
+/// Pasada de pantalla completa sobre `target`. Sin pipeline solo aplica `load`.
+fn fullscreen_pass(
+    encoder: &mut wgpu::CommandEncoder,
+    label: &str,
+    target: &wgpu::TextureView,
+    load: wgpu::LoadOp<wgpu::Color>,
+    draw: Option<(&wgpu::RenderPipeline, &wgpu::BindGroup)>,
+) {
+    let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+        label: Some(label),
+        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+            view: target,
+            resolve_target: None,
+            ops: wgpu::Operations {
+                load,
+                store: wgpu::StoreOp::Store,
+            },
+        })],
+        depth_stencil_attachment: None,
+        occlusion_query_set: None,
+        timestamp_writes: None,
+    });
+
+    if let Some((pipeline, bind_group)) = draw {
+        pass.set_pipeline(pipeline);
+        pass.set_bind_group(0, bind_group, &[]);
+        pass.draw(0..3, 0..1);
+    }
+}
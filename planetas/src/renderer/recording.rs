@@ -0,0 +1,123 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use winit::event::MouseButton;
+use winit::keyboard::KeyCode;
+
+use super::clock::TimeSettings;
+
+/// Entrada que recibe el renderer. Todas pasan por `Renderer::handle_input`,
+/// que es lo que se graba y se reproduce.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum InputEvent {
+    Key { key: KeyCode, pressed: bool },
+    MouseButton { button: MouseButton, pressed: bool },
+    /// Posición del cursor en píxeles de la ventana
+    CursorMoved { x: f32, y: f32 },
+    /// Movimiento relativo del ratón en píxeles
+    MouseMotion { dx: f32, dy: f32 },
+    /// Pasos de la rueda del ratón
+    Scroll { steps: f32 },
+    /// Capturar o liberar el cursor desde fuera (por ejemplo al perder el foco)
+    MouseLook { enabled: bool },
+}
+
+/// Sesión grabada: estado inicial y cada entrada con el paso fijo de la
+/// simulación en que se aplicó. Como la simulación avanza en pasos fijos,
+/// reproducirla desde el mismo estado inicial da exactamente el mismo recorrido.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Recording {
+    /// Tamaño de la ventana al grabar (el picking depende de él)
+    pub width: u32,
+    pub height: u32,
+    pub time: TimeSettings,
+    /// `Scene::hash` de la escena con que se grabó; None en grabaciones anteriores
+    #[serde(default)]
+    pub scene: Option<u64>,
+    /// Si la sesión empezó en el modo gravedad
+    #[serde(default)]
+    pub gravity: bool,
+    /// Pasos simulados en total
+    pub steps: u64,
+    /// (paso, entrada), en orden
+    pub events: Vec<(u64, InputEvent)>,
+}
+
+#[derive(Debug)]
+pub enum RecordingError {
+    Io { path: PathBuf, source: std::io::Error },
+    Parse { path: PathBuf, source: ron::error::SpannedError },
+    Serialize(ron::Error),
+}
+
+impl fmt::Display for RecordingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecordingError::Io { path, source } => write!(f, "{}: {}", path.display(), source),
+            RecordingError::Parse { path, source } => write!(f, "{}:{}", path.display(), source),
+            RecordingError::Serialize(source) => write!(f, "no se pudo serializar la grabación: {}", source),
+        }
+    }
+}
+
+impl std::error::Error for RecordingError {}
+
+impl Recording {
+    pub fn new(width: u32, height: u32, time: TimeSettings, scene: u64) -> Self {
+        Self {
+            width,
+            height,
+            time,
+            scene: Some(scene),
+            gravity: false,
+            steps: 0,
+            events: Vec::new(),
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, RecordingError> {
+        let source = std::fs::read_to_string(path).map_err(|source| RecordingError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        ron::from_str(&source).map_err(|source| RecordingError::Parse {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), RecordingError> {
+        let config = ron::ser::PrettyConfig::new().depth_limit(2);
+        let text = ron::ser::to_string_pretty(self, config).map_err(RecordingError::Serialize)?;
+        std::fs::write(path, text).map_err(|source| RecordingError::Io {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+}
+
+/// Reproducción en curso: entrega los eventos de cada paso antes de simularlo.
+pub struct Replay {
+    recording: Recording,
+    next_event: usize,
+}
+
+impl Replay {
+    pub fn new(recording: Recording) -> Self {
+        Self { recording, next_event: 0 }
+    }
+
+    /// Eventos que se aplicaron antes del paso `step`.
+    pub fn events_for_step(&mut self, step: u64) -> Vec<InputEvent> {
+        let pending = &self.recording.events[self.next_event..];
+        let count = pending.iter().take_while(|(event_step, _)| *event_step <= step).count();
+        self.next_event += count;
+        pending[..count].iter().map(|(_, event)| *event).collect()
+    }
+
+    /// La reproducción termina al llegar al último paso grabado.
+    pub fn finished(&self, step: u64) -> bool {
+        step >= self.recording.steps
+    }
+}
@@ -0,0 +1,399 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use glam::Vec3;
+use serde::Deserialize;
+
+use super::collision::CollisionSettings;
+use super::model::{ModelSettings, Prop};
+use super::planets::{flatten_system, Planet, WarpPoint};
+use super::skybox::SkyboxSettings;
+
+/// Número máximo de puntos de warp: uno por cada tecla del 1 al 9.
+pub const MAX_WARP_POINTS: usize = 9;
+/// Distancia mínima entre la superficie de una luna y la de su padre en el periapsis.
+const PERIAPSIS_MARGIN: f32 = 0.05;
+
+/// Sistema solar completo cargado desde un archivo RON.
+///
+/// Tras `parse`, `bodies` contiene el árbol ya aplanado (ver `planets::flatten_system`).
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scene {
+    pub bodies: Vec<Planet>,
+    #[serde(default)]
+    pub warp_points: Vec<WarpPoint>,
+    #[serde(default)]
+    pub light: LightSettings,
+    #[serde(default)]
+    pub collision: CollisionSettings,
+    /// Modelo que sustituye a la nave hecha a mano
+    #[serde(default)]
+    pub ship_model: Option<ModelSettings>,
+    #[serde(default)]
+    pub props: Vec<Prop>,
+    #[serde(default)]
+    pub skybox: SkyboxSettings,
+    /// Huella del texto de la escena; las grabaciones la guardan para avisar si
+    /// se reproducen con otra escena
+    #[serde(skip)]
+    pub hash: u64,
+}
+
+/// Luz emitida por el cuerpo `Sun` de la escena.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct LightSettings {
+    pub color: Vec3,
+    pub intensity: f32,
+    pub ambient: f32,
+}
+
+impl Default for LightSettings {
+    fn default() -> Self {
+        Self {
+            color: Vec3::new(1.0, 0.95, 0.9),
+            intensity: 1.0,
+            ambient: 0.15,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum SceneError {
+    Io { path: PathBuf, source: std::io::Error },
+    Parse { origin: String, source: ron::error::SpannedError },
+    Invalid { field: String, message: String },
+}
+
+impl fmt::Display for SceneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SceneError::Io { path, source } => {
+                write!(f, "no se pudo leer la escena {}: {}", path.display(), source)
+            }
+            SceneError::Parse { origin, source } => write!(f, "{}:{}", origin, source),
+            SceneError::Invalid { field, message } => write!(f, "{}: {}", field, message),
+        }
+    }
+}
+
+impl std::error::Error for SceneError {}
+
+impl Scene {
+    /// Escena por defecto embebida en el binario (`scenes/fiesta.ron`).
+    pub fn default_scene() -> Self {
+        Self::parse(include_str!("../../scenes/fiesta.ron"), "scenes/fiesta.ron")
+            .expect("la escena por defecto debe ser válida")
+    }
+
+    pub fn load(path: &Path) -> Result<Self, SceneError> {
+        let source = std::fs::read_to_string(path).map_err(|source| SceneError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Self::parse(&source, &path.display().to_string())
+    }
+
+    pub fn parse(source: &str, origin: &str) -> Result<Self, SceneError> {
+        let options = ron::Options::default()
+            .with_default_extension(ron::extensions::Extensions::IMPLICIT_SOME);
+        let mut scene: Scene = options.from_str(source).map_err(|source| SceneError::Parse {
+            origin: origin.to_string(),
+            source,
+        })?;
+        scene.validate()?;
+        scene.hash = fnv1a(source.as_bytes());
+        // Las texturas y los modelos se buscan junto al archivo de escena
+        let base_dir = Path::new(origin).parent().unwrap_or(Path::new(""));
+        for (i, body) in scene.bodies.iter_mut().enumerate() {
+            load_materials(body, &format!("bodies[{}]", i), base_dir)?;
+        }
+        if let Some(model) = &mut scene.ship_model {
+            load_model(model, "ship_model", base_dir)?;
+        }
+        for (i, prop) in scene.props.iter_mut().enumerate() {
+            load_model(&mut prop.model, &format!("props[{}].model", i), base_dir)?;
+        }
+        scene.skybox.load_images(base_dir).map_err(|(field, message)| SceneError::Invalid {
+            field: format!("skybox.{}", field),
+            message,
+        })?;
+        scene.bodies = flatten_system(std::mem::take(&mut scene.bodies));
+        scene.resolve_warp_bodies()?;
+        scene.resolve_prop_bodies()?;
+        Ok(scene)
+    }
+
+    fn validate(&self) -> Result<(), SceneError> {
+        check(!self.bodies.is_empty(), "bodies", "la escena debe tener al menos un cuerpo")?;
+
+        // Warps, padres y caminos de cámara buscan los cuerpos por nombre
+        let mut names = HashMap::new();
+        for (i, body) in self.bodies.iter().enumerate() {
+            validate_body(body, &format!("bodies[{}]", i), None, &mut names)?;
+        }
+
+        check(
+            self.light.color.is_finite() && self.light.color.min_element() >= 0.0,
+            "light.color",
+            "cada componente debe ser mayor o igual que 0",
+        )?;
+        check(
+            self.light.intensity.is_finite() && self.light.intensity >= 0.0,
+            "light.intensity",
+            "debe ser mayor o igual que 0",
+        )?;
+        check(
+            self.light.ambient.is_finite() && self.light.ambient >= 0.0,
+            "light.ambient",
+            "debe ser mayor o igual que 0",
+        )?;
+
+        check(
+            self.collision.margin.is_finite() && self.collision.margin > 0.0,
+            "collision.margin",
+            "debe ser mayor que 0",
+        )?;
+        check(
+            (0.0..=1.0).contains(&self.collision.restitution),
+            "collision.restitution",
+            "debe estar en [0, 1]",
+        )?;
+        check(
+            self.collision.bounce_speed.is_finite() && self.collision.bounce_speed >= 0.0,
+            "collision.bounce_speed",
+            "debe ser mayor o igual que 0",
+        )?;
+        check(
+            self.collision.damage_speed.is_finite() && self.collision.damage_speed >= 0.0,
+            "collision.damage_speed",
+            "debe ser mayor o igual que 0",
+        )?;
+
+        check(
+            self.warp_points.len() <= MAX_WARP_POINTS,
+            "warp_points",
+            &format!("como máximo {} puntos (teclas 1-9)", MAX_WARP_POINTS),
+        )?;
+
+        for (i, warp) in self.warp_points.iter().enumerate() {
+            let field = |name: &str| format!("warp_points[{}].{}", i, name);
+
+            check(!warp.name.trim().is_empty(), &field("name"), "no puede estar vacío")?;
+            check(warp.position.is_finite(), &field("position"), "debe ser un vector finito")?;
+            check(warp.target.is_finite(), &field("target"), "debe ser un vector finito")?;
+            check(
+                warp.position.distance(warp.target) > f32::EPSILON,
+                &field("target"),
+                "debe ser distinto de position",
+            )?;
+        }
+
+        check(
+            self.skybox.image.is_none() || self.skybox.faces.is_none(),
+            "skybox.faces",
+            "no se puede usar junto con image",
+        )?;
+        check(
+            self.skybox.faces.as_ref().is_none_or(|faces| faces.len() == 6),
+            "skybox.faces",
+            "debe tener 6 imágenes (+X, -X, +Y, -Y, +Z, -Z)",
+        )?;
+        check(
+            !self.skybox.bake || (self.skybox.image.is_none() && self.skybox.faces.is_none()),
+            "skybox.bake",
+            "solo se aplica al cielo procedural (sin image ni faces)",
+        )?;
+        check(
+            (16..=4096).contains(&self.skybox.resolution),
+            "skybox.resolution",
+            "debe estar entre 16 y 4096",
+        )?;
+        check(
+            self.skybox.intensity.is_finite() && self.skybox.intensity >= 0.0,
+            "skybox.intensity",
+            "debe ser mayor o igual que 0",
+        )?;
+
+        if let Some(model) = &self.ship_model {
+            validate_model(model, "ship_model")?;
+        }
+        for (i, prop) in self.props.iter().enumerate() {
+            let field = |name: &str| format!("props[{}].{}", i, name);
+
+            check(!prop.name.trim().is_empty(), &field("name"), "no puede estar vacío")?;
+            check(prop.position.is_finite(), &field("position"), "debe ser un vector finito")?;
+            check_finite(prop.spin, &field("spin"))?;
+            validate_model(&prop.model, &field("model"))?;
+        }
+
+        Ok(())
+    }
+
+    /// Enlaza cada warp con `body` al índice de ese cuerpo en la lista aplanada.
+    fn resolve_warp_bodies(&mut self) -> Result<(), SceneError> {
+        for (i, warp) in self.warp_points.iter_mut().enumerate() {
+            let Some(body_name) = &warp.body else {
+                continue;
+            };
+            let index = find_body(&self.bodies, body_name, &format!("warp_points[{}].body", i))?;
+            check(
+                warp.position.length() > self.bodies[index].radius(),
+                &format!("warp_points[{}].position", i),
+                "debe quedar fuera del cuerpo",
+            )?;
+            warp.body_index = Some(index);
+        }
+
+        Ok(())
+    }
+
+    /// Igual que los warps: los props con `body` se colocan respecto a ese cuerpo.
+    fn resolve_prop_bodies(&mut self) -> Result<(), SceneError> {
+        for (i, prop) in self.props.iter_mut().enumerate() {
+            if let Some(body_name) = &prop.body {
+                prop.body_index = Some(find_body(&self.bodies, body_name, &format!("props[{}].body", i))?);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn find_body(bodies: &[Planet], name: &str, field: &str) -> Result<usize, SceneError> {
+    bodies.iter()
+        .position(|body| body.name == name)
+        .ok_or_else(|| SceneError::Invalid {
+            field: field.to_string(),
+            message: format!("no existe un cuerpo llamado `{}`", name),
+        })
+}
+
+/// `names` guarda la ruta de cada nombre ya visto en el árbol, para rechazar repetidos.
+fn validate_body(
+    body: &Planet,
+    path: &str,
+    parent: Option<&Planet>,
+    names: &mut HashMap<String, String>,
+) -> Result<(), SceneError> {
+    let field = |name: &str| format!("{} ({}).{}", path, body.name, name);
+
+    check(!body.name.trim().is_empty(), &format!("{}.name", path), "no puede estar vacío")?;
+    if let Some(first) = names.insert(body.name.clone(), path.to_string()) {
+        return Err(SceneError::Invalid {
+            field: format!("{}.name", path),
+            message: format!("`{}` ya es el nombre de {}", body.name, first),
+        });
+    }
+    check(body.scale.is_finite() && body.scale > 0.0, &field("scale"), "debe ser mayor que 0")?;
+    check_finite(body.rotation_speed, &field("rotation_speed"))?;
+    if let Some(mass) = body.mass {
+        check(mass.is_finite() && mass > 0.0, &field("mass"), "debe ser mayor que 0")?;
+    }
+
+    if let Some(material) = &body.material {
+        check((0.0..=1.0).contains(&material.mix), &field("material.mix"), "debe estar en [0, 1]")?;
+        for (name, value) in [
+            ("normal_strength", material.normal_strength),
+            ("emissive_strength", material.emissive_strength),
+            ("specular_strength", material.specular_strength),
+        ] {
+            check(
+                value.is_finite() && value >= 0.0,
+                &field(&format!("material.{}", name)),
+                "debe ser mayor o igual que 0",
+            )?;
+        }
+    }
+
+    if let Some(orbit) = &body.orbit {
+        check(
+            orbit.semi_major_axis.is_finite() && orbit.semi_major_axis > 0.0,
+            &field("orbit.semi_major_axis"),
+            "debe ser mayor que 0",
+        )?;
+        check(
+            (0.0..1.0).contains(&orbit.eccentricity),
+            &field("orbit.eccentricity"),
+            "debe estar en [0, 1) (solo órbitas elípticas)",
+        )?;
+        check_finite(orbit.inclination, &field("orbit.inclination"))?;
+        check_finite(orbit.longitude_of_ascending_node, &field("orbit.longitude_of_ascending_node"))?;
+        check_finite(orbit.argument_of_periapsis, &field("orbit.argument_of_periapsis"))?;
+        check_finite(orbit.mean_anomaly_at_epoch, &field("orbit.mean_anomaly_at_epoch"))?;
+        check_finite(orbit.mean_motion, &field("orbit.mean_motion"))?;
+
+        // Una luna no puede pasar por dentro de su padre
+        if let Some(parent) = parent {
+            let min_periapsis = parent.radius() + body.radius() + PERIAPSIS_MARGIN;
+            check(
+                orbit.periapsis() >= min_periapsis,
+                &field("orbit.semi_major_axis"),
+                &format!(
+                    "el periapsis ({:.2}) toca a {}; debe ser al menos {:.2}",
+                    orbit.periapsis(),
+                    parent.name,
+                    min_periapsis
+                ),
+            )?;
+        }
+    }
+
+    for (i, moon) in body.moons.iter().enumerate() {
+        validate_body(moon, &format!("{}.moons[{}]", path, i), Some(body), names)?;
+    }
+
+    Ok(())
+}
+
+fn load_materials(body: &mut Planet, path: &str, base_dir: &Path) -> Result<(), SceneError> {
+    if let Some(material) = &mut body.material {
+        material.load_images(base_dir).map_err(|(name, message)| SceneError::Invalid {
+            field: format!("{} ({}).material.{}", path, body.name, name),
+            message,
+        })?;
+    }
+
+    for (i, moon) in body.moons.iter_mut().enumerate() {
+        load_materials(moon, &format!("{}.moons[{}]", path, i), base_dir)?;
+    }
+
+    Ok(())
+}
+
+fn validate_model(model: &ModelSettings, path: &str) -> Result<(), SceneError> {
+    check(model.scale.is_finite() && model.scale > 0.0, &format!("{}.scale", path), "debe ser mayor que 0")?;
+    check(model.rotation.is_finite(), &format!("{}.rotation", path), "debe ser un vector finito")
+}
+
+fn load_model(model: &mut ModelSettings, path: &str, base_dir: &Path) -> Result<(), SceneError> {
+    model.load_mesh(base_dir).map_err(|message| SceneError::Invalid {
+        field: format!("{}.path", path),
+        message,
+    })
+}
+
+fn check(condition: bool, field: &str, message: &str) -> Result<(), SceneError> {
+    if condition {
+        Ok(())
+    } else {
+        Err(SceneError::Invalid {
+            field: field.to_string(),
+            message: message.to_string(),
+        })
+    }
+}
+
+fn check_finite(value: f32, field: &str) -> Result<(), SceneError> {
+    check(value.is_finite(), field, "debe ser un número finito")
+}
+
+/// Hash FNV-1a de 64 bits: estable entre versiones de Rust y plataformas, a
+/// diferencia de `DefaultHasher`, así que sirve para guardarlo en archivos.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
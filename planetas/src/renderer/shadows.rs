@@ -0,0 +1,155 @@
+use bytemuck::{Pod, Zeroable};
+use glam::{Mat4, Vec3};
+use wgpu::util::DeviceExt;
+
+/// Resolución de cada una de las 6 caras del mapa de sombras.
+pub const SHADOW_MAP_SIZE: u32 = 1024;
+/// Distancia máxima desde el sol que cubre el mapa de sombras.
+pub const SHADOW_FAR: f32 = 200.0;
+const SHADOW_NEAR: f32 = 0.5;
+
+/// Dirección y vector up de cada cara del cubemap, en el orden de capas de wgpu
+/// (+X, -X, +Y, -Y, +Z, -Z). Con `look_at_lh` la orientación de cada imagen
+/// coincide con la que espera el muestreo de `texture_depth_cube`.
+const FACES: [(Vec3, Vec3); 6] = [
+    (Vec3::X, Vec3::Y),
+    (Vec3::NEG_X, Vec3::Y),
+    (Vec3::Y, Vec3::NEG_Z),
+    (Vec3::NEG_Y, Vec3::Z),
+    (Vec3::Z, Vec3::Y),
+    (Vec3::NEG_Z, Vec3::Y),
+];
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Pod, Zeroable)]
+pub struct ShadowFaceUniforms {
+    pub view_proj: [[f32; 4]; 4],
+    pub light_position: [f32; 3],
+    pub far: f32,
+}
+
+/// Mapa de sombras omnidireccional del sol: cubemap de profundidad de 6 caras de 90°. Cada texel guarda la distancia lineal
+/// al sol dividida por `SHADOW_FAR`.
+pub struct ShadowMap {
+    pub face_views: Vec<wgpu::TextureView>,
+    pub cube_view: wgpu::TextureView,
+    pub sampler: wgpu::Sampler,
+    pub face_uniform_buffers: Vec<wgpu::Buffer>,
+    pub face_bind_groups: Vec<wgpu::BindGroup>,
+    pub face_bind_group_layout: wgpu::BindGroupLayout,
+}
+
+impl ShadowMap {
+    pub fn new(device: &wgpu::Device) -> Self {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Shadow Map"),
+            size: wgpu::Extent3d {
+                width: SHADOW_MAP_SIZE,
+                height: SHADOW_MAP_SIZE,
+                depth_or_array_layers: 6,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Depth32Float,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+
+        let face_views = (0..6)
+            .map(|face| {
+                texture.create_view(&wgpu::TextureViewDescriptor {
+                    label: Some(&format!("Shadow Map Face {}", face)),
+                    dimension: Some(wgpu::TextureViewDimension::D2),
+                    base_array_layer: face,
+                    array_layer_count: Some(1),
+                    ..Default::default()
+                })
+            })
+            .collect();
+
+        let cube_view = texture.create_view(&wgpu::TextureViewDescriptor {
+            label: Some("Shadow Map Cube View"),
+            dimension: Some(wgpu::TextureViewDimension::Cube),
+            ..Default::default()
+        });
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Shadow Sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            compare: Some(wgpu::CompareFunction::LessEqual),
+            ..Default::default()
+        });
+
+        let face_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+            label: Some("shadow_face_bind_group_layout"),
+        });
+
+        let mut face_uniform_buffers = Vec::new();
+        let mut face_bind_groups = Vec::new();
+        for (face, view_proj) in face_view_projs(Vec3::ZERO).iter().enumerate() {
+            let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some(&format!("Shadow Face Uniform Buffer {}", face)),
+                contents: bytemuck::cast_slice(&[ShadowFaceUniforms {
+                    view_proj: view_proj.to_cols_array_2d(),
+                    light_position: [0.0; 3],
+                    far: SHADOW_FAR,
+                }]),
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            });
+
+            let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                layout: &face_bind_group_layout,
+                entries: &[wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: buffer.as_entire_binding(),
+                }],
+                label: Some(&format!("shadow_face_bind_group {}", face)),
+            });
+
+            face_uniform_buffers.push(buffer);
+            face_bind_groups.push(bind_group);
+        }
+
+        Self {
+            face_views,
+            cube_view,
+            sampler,
+            face_uniform_buffers,
+            face_bind_groups,
+            face_bind_group_layout,
+        }
+    }
+
+    /// Actualiza las matrices de las 6 caras para la posición actual del sol.
+    pub fn update(&self, queue: &wgpu::Queue, light_position: Vec3) {
+        for (face, view_proj) in face_view_projs(light_position).iter().enumerate() {
+            let uniforms = ShadowFaceUniforms {
+                view_proj: view_proj.to_cols_array_2d(),
+                light_position: light_position.to_array(),
+                far: SHADOW_FAR,
+            };
+            queue.write_buffer(&self.face_uniform_buffers[face], 0, bytemuck::cast_slice(&[uniforms]));
+        }
+    }
+}
+
+/// Matrices vista-proyección (90°, aspecto 1) de las 6 caras vistas desde el sol.
+fn face_view_projs(light_position: Vec3) -> [Mat4; 6] {
+    let proj = Mat4::perspective_lh(std::f32::consts::FRAC_PI_2, 1.0, SHADOW_NEAR, SHADOW_FAR);
+    FACES.map(|(dir, up)| proj * Mat4::look_at_lh(light_position, light_position + dir, up))
+}
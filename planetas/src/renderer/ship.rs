@@ -1,51 +1,53 @@
+use glam::{Vec2, Vec3};
+
+use super::geometry::flat_shaded;
 use super::types::Vertex;
 
 pub fn create_ship() -> (Vec<Vertex>, Vec<u32>) {
-    let mut vertices = Vec::new();
-    let mut indices = Vec::new();
+    let positions = [
+        // Cuerpo principal de la nave (prisma triangular)
+        // Punta delantera
+        Vec3::new(0.0, 0.0, 1.5),
+        // Base trasera (triángulo)
+        Vec3::new(-0.5, 0.3, -0.5),
+        Vec3::new(0.5, 0.3, -0.5),
+        Vec3::new(0.0, -0.3, -0.5),
+        // Alas
+        Vec3::new(-1.2, 0.0, 0.0), // Ala izquierda
+        Vec3::new(1.2, 0.0, 0.0),  // Ala derecha
+        // Cabina (pequeña elevación)
+        Vec3::new(0.0, 0.5, 0.3),
+        // Cola
+        Vec3::new(0.0, 0.6, -0.8),
+    ];
 
-    // Cuerpo principal de la nave (prisma triangular)
-    // Punta delantera
-    vertices.push(Vertex { position: [0.0, 0.0, 1.5] });
-    
-    // Base trasera (triángulo)
-    vertices.push(Vertex { position: [-0.5, 0.3, -0.5] });
-    vertices.push(Vertex { position: [0.5, 0.3, -0.5] });
-    vertices.push(Vertex { position: [0.0, -0.3, -0.5] });
-    
-    // Alas
-    vertices.push(Vertex { position: [-1.2, 0.0, 0.0] }); // Ala izquierda
-    vertices.push(Vertex { position: [1.2, 0.0, 0.0] });  // Ala derecha
-    
-    // Cabina (pequeña elevación)
-    vertices.push(Vertex { position: [0.0, 0.5, 0.3] });
-    
-    // Cola
-    vertices.push(Vertex { position: [0.0, 0.6, -0.8] });
-    
-    // Caras del cuerpo principal
-    // Cara superior
-    indices.extend_from_slice(&[0, 1, 6]);
-    indices.extend_from_slice(&[0, 6, 2]);
-    
-    // Cara inferior
-    indices.extend_from_slice(&[0, 3, 1]);
-    indices.extend_from_slice(&[0, 2, 3]);
-    
-    // Lados
-    indices.extend_from_slice(&[1, 3, 6]);
-    indices.extend_from_slice(&[2, 6, 3]);
-    
-    // Trasera
-    indices.extend_from_slice(&[1, 2, 3]);
-    
-    // Alas
-    indices.extend_from_slice(&[0, 4, 1]);
-    indices.extend_from_slice(&[0, 2, 5]);
-    
-    // Cola
-    indices.extend_from_slice(&[6, 7, 1]);
-    indices.extend_from_slice(&[6, 2, 7]);
+    // Triángulos en sentido antihorario vistos desde fuera
+    let triangles = [
+        // Cara superior
+        [0, 6, 1],
+        [0, 2, 6],
+        // Cara inferior
+        [0, 1, 3],
+        [0, 3, 2],
+        // Lados
+        [1, 3, 6],
+        [2, 6, 3],
+        // Trasera
+        [1, 2, 3],
+        // Alas (láminas: una cara hacia arriba y otra hacia abajo)
+        [0, 1, 4],
+        [0, 4, 1],
+        [0, 5, 2],
+        [0, 2, 5],
+        // Cola (también lámina)
+        [6, 7, 1],
+        [6, 1, 7],
+        [6, 2, 7],
+        [6, 7, 2],
+    ];
 
-    (vertices, indices)
-}
\ No newline at end of file
+    // UV proyectadas desde arriba: U de ala a ala, V de la punta a la cola
+    flat_shaded(&positions, &triangles, |position| {
+        Vec2::new((position.x + 1.2) / 2.4, (1.5 - position.z) / 2.3)
+    })
+}
@@ -1,20 +1,98 @@
+use std::path::{Path, PathBuf};
+
+use bytemuck::{Pod, Zeroable};
+use serde::Deserialize;
+use wgpu::util::DeviceExt;
+
+use super::material::TextureData;
+use super::pipelines::ShaderLibrary;
 use super::types::Vertex;
 
+/// Formato de los cubemaps que se generan al arrancar (panorama convertido o
+/// cielo procedural horneado).
+pub const SKY_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba16Float;
+
+/// Fondo de la escena. Sin imágenes ni `bake` el cielo procedural se calcula en
+/// cada píxel y cada frame; con `bake` se calcula una sola vez en un cubemap
+/// (quieto: sin parpadeo ni nebulosa animada).
+///
+/// Las rutas son relativas al archivo de escena y pueden ser HDR, PNG o KTX2.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct SkyboxSettings {
+    /// Panorama equirectangular, que se convierte a cubemap al arrancar
+    pub image: Option<PathBuf>,
+    /// Cubemap en seis imágenes cuadradas: +X, -X, +Y, -Y, +Z, -Z
+    pub faces: Option<Vec<PathBuf>>,
+    pub bake: bool,
+    /// Lado en píxeles de cada cara de los cubemaps generados
+    pub resolution: u32,
+    /// Multiplicador del brillo del cielo en cubemap
+    pub intensity: f32,
+    /// Imágenes ya decodificadas por `load_images` (el panorama o las seis caras)
+    #[serde(skip)]
+    pub images: Vec<TextureData>,
+}
+
+impl Default for SkyboxSettings {
+    fn default() -> Self {
+        Self {
+            image: None,
+            faces: None,
+            bake: false,
+            resolution: 1024,
+            intensity: 1.0,
+            images: Vec::new(),
+        }
+    }
+}
+
+impl SkyboxSettings {
+    /// Lee las imágenes (rutas relativas a `base_dir`). En caso de error devuelve
+    /// el campo que falla y el mensaje.
+    pub fn load_images(&mut self, base_dir: &Path) -> Result<(), (String, String)> {
+        let paths: Vec<(String, &PathBuf)> = match (&self.image, &self.faces) {
+            (Some(image), _) => vec![("image".to_string(), image)],
+            (None, Some(faces)) => faces.iter().enumerate().map(|(i, path)| (format!("faces[{}]", i), path)).collect(),
+            (None, None) => Vec::new(),
+        };
+
+        let mut images: Vec<TextureData> = Vec::new();
+        for (field, path) in paths {
+            let path = base_dir.join(path);
+            let image = TextureData::load(&path, true)
+                .map_err(|message| (field.clone(), format!("no se pudo cargar {}: {}", path.display(), message)))?;
+            // Las caras deben poder formar un cubemap: cuadradas e iguales
+            if let Some(first) = images.first() {
+                if (image.width, image.height, image.format) != (first.width, first.height, first.format) {
+                    return Err((field, "todas las caras deben tener el mismo tamaño y formato".to_string()));
+                }
+            } else if self.faces.is_some() && image.width != image.height {
+                return Err((field, "las caras deben ser cuadradas".to_string()));
+            }
+            images.push(image);
+        }
+
+        self.images = images;
+        Ok(())
+    }
+}
+
 pub fn create_skybox() -> (Vec<Vertex>, Vec<u32>) {
     let size = 150.0;
     
     let vertices = vec![
         // Cara frontal
-        Vertex { position: [-size, -size, size] },
-        Vertex { position: [size, -size, size] },
-        Vertex { position: [size, size, size] },
-        Vertex { position: [-size, size, size] },
+        Vertex { position: [-size, -size, size], ..Default::default() },
+        Vertex { position: [size, -size, size], ..Default::default() },
+        Vertex { position: [size, size, size], ..Default::default() },
+        Vertex { position: [-size, size, size], ..Default::default() },
         
         // Cara trasera
-        Vertex { position: [-size, -size, -size] },
-        Vertex { position: [size, -size, -size] },
-        Vertex { position: [size, size, -size] },
-        Vertex { position: [-size, size, -size] },
+        Vertex { position: [-size, -size, -size], ..Default::default() },
+        Vertex { position: [size, -size, -size], ..Default::default() },
+        Vertex { position: [size, size, -size], ..Default::default() },
+        Vertex { position: [-size, size, -size], ..Default::default() },
     ];
 
     let indices = vec![
@@ -33,4 +111,321 @@ pub fn create_skybox() -> (Vec<Vertex>, Vec<u32>) {
     ];
 
     (vertices, indices)
-}
\ No newline at end of file
+}
+/// Parámetros del cielo en cubemap (grupo 1 del pipeline del skybox).
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Pod, Zeroable)]
+struct SkyUniforms {
+    intensity: f32,
+    _padding: [f32; 3],
+}
+
+/// Cara que dibuja cada pasada de `bake_cubemap`.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Pod, Zeroable)]
+struct BakeUniforms {
+    face: u32,
+    _padding: [u32; 3],
+}
+
+/// Cielo en cubemap ya en la GPU: cargado de seis imágenes o generado al
+/// arrancar a partir de un panorama o del cielo procedural.
+pub struct SkyCubemap {
+    pub bind_group: wgpu::BindGroup,
+}
+
+impl SkyCubemap {
+    pub fn create_bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
+        device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("sky_bind_group_layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::Cube,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        })
+    }
+
+    /// Prepara el cubemap que piden los ajustes. Devuelve None para el cielo
+    /// procedural de cada frame (o si la GPU no admite las imágenes o la resolución).
+    pub fn new(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        shaders: &ShaderLibrary,
+        layout: &wgpu::BindGroupLayout,
+        settings: &SkyboxSettings,
+    ) -> Option<Self> {
+        let texture = if settings.faces.is_some() {
+            if let Err(message) = check_faces(device, &settings.images[0]) {
+                println!("[skybox] skybox.faces: {}, se usa el cielo procedural", message);
+                return None;
+            }
+            upload_faces(device, queue, &settings.images)
+        } else if settings.image.is_some() {
+            let panorama = &settings.images[0];
+            if let Err(message) = panorama
+                .check_device(device)
+                .map_err(|message| format!("skybox.image: {}", message))
+                .and_then(|()| check_resolution(device, settings.resolution)) {
+                println!("[skybox] {}, se usa el cielo procedural", message);
+                return None;
+            }
+            let panorama = panorama.upload(device, queue, "Sky Panorama");
+            bake_cubemap(device, queue, shaders, settings.resolution, "fs_panorama", &panorama)
+        } else if settings.bake {
+            if let Err(message) = check_resolution(device, settings.resolution) {
+                println!("[skybox] {}, se usa el cielo procedural", message);
+                return None;
+            }
+            // El cielo procedural no lee ninguna textura; basta una de 1x1
+            let placeholder = TextureData::solid([0; 4], false).upload(device, queue, "Sky Placeholder");
+            bake_cubemap(device, queue, shaders, settings.resolution, "fs_starfield", &placeholder)
+        } else {
+            return None;
+        };
+
+        let view = texture.create_view(&wgpu::TextureViewDescriptor {
+            label: Some("Sky Cubemap View"),
+            dimension: Some(wgpu::TextureViewDimension::Cube),
+            ..Default::default()
+        });
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Sky Sampler"),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+        let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Sky Uniform Buffer"),
+            contents: bytemuck::cast_slice(&[SkyUniforms {
+                intensity: settings.intensity,
+                _padding: [0.0; 3],
+            }]),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("sky_bind_group"),
+            layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: uniform_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(&view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::Sampler(&sampler),
+                },
+            ],
+        });
+
+        Some(Self { bind_group })
+    }
+}
+
+/// Las caras se muestrean con filtro lineal como cubemap, así que su formato
+/// tiene que admitirlo además de caber en la GPU.
+fn check_faces(device: &wgpu::Device, face: &TextureData) -> Result<(), String> {
+    face.check_device(device)?;
+    let features = face.format.guaranteed_format_features(device.features());
+    if !features.allowed_usages.contains(wgpu::TextureUsages::TEXTURE_BINDING)
+        || !features.flags.contains(wgpu::TextureFormatFeatureFlags::FILTERABLE)
+    {
+        return Err(format!("el formato {:?} no se puede muestrear con filtro", face.format));
+    }
+    Ok(())
+}
+
+/// La escena limita `resolution` a 4096, pero la GPU puede admitir menos.
+fn check_resolution(device: &wgpu::Device, resolution: u32) -> Result<(), String> {
+    let max = device.limits().max_texture_dimension_2d;
+    if resolution > max {
+        return Err(format!("skybox.resolution ({}) supera el tamaño máximo de la GPU ({})", resolution, max));
+    }
+    Ok(())
+}
+
+/// Sube las seis caras como capas de un cubemap, con los mipmaps que tengan en común.
+fn upload_faces(device: &wgpu::Device, queue: &wgpu::Queue, faces: &[TextureData]) -> wgpu::Texture {
+    let levels = faces.iter().map(|face| face.levels.len()).min().unwrap_or(1);
+    let data: Vec<u8> = faces.iter().flat_map(|face| face.levels[..levels].concat()).collect();
+
+    device.create_texture_with_data(
+        queue,
+        &wgpu::TextureDescriptor {
+            label: Some("Sky Cubemap"),
+            size: wgpu::Extent3d {
+                width: faces[0].width,
+                height: faces[0].height,
+                depth_or_array_layers: 6,
+            },
+            mip_level_count: levels as u32,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: faces[0].format,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        },
+        wgpu::util::TextureDataOrder::LayerMajor,
+        &data,
+    )
+}
+
+/// Genera un cubemap de `resolution` píxeles por cara dibujando cada cara con
+/// el fragment shader `fragment_entry` de `sky_bake.wgsl`.
+fn bake_cubemap(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    shaders: &ShaderLibrary,
+    resolution: u32,
+    fragment_entry: &str,
+    panorama: &wgpu::Texture,
+) -> wgpu::Texture {
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("Sky Cubemap"),
+        size: wgpu::Extent3d {
+            width: resolution,
+            height: resolution,
+            depth_or_array_layers: 6,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: SKY_FORMAT,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+        view_formats: &[],
+    });
+
+    let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        label: Some("sky_bake_bind_group_layout"),
+        entries: &[
+            wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 1,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Texture {
+                    sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                    view_dimension: wgpu::TextureViewDimension::D2,
+                    multisampled: false,
+                },
+                count: None,
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 2,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                count: None,
+            },
+        ],
+    });
+    let pipeline = super::pipelines::create_sky_bake_pipeline(device, shaders, &layout, fragment_entry);
+
+    let panorama_view = panorama.create_view(&wgpu::TextureViewDescriptor::default());
+    // El panorama da la vuelta en U; en V se ajusta al borde (los polos)
+    let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+        label: Some("Sky Panorama Sampler"),
+        address_mode_u: wgpu::AddressMode::Repeat,
+        address_mode_v: wgpu::AddressMode::ClampToEdge,
+        mag_filter: wgpu::FilterMode::Linear,
+        min_filter: wgpu::FilterMode::Linear,
+        ..Default::default()
+    });
+
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("Sky Bake Encoder"),
+    });
+    for face in 0..6 {
+        let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some(&format!("Sky Bake Uniform Buffer {}", face)),
+            contents: bytemuck::cast_slice(&[BakeUniforms {
+                face,
+                _padding: [0; 3],
+            }]),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some(&format!("sky_bake_bind_group {}", face)),
+            layout: &layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: uniform_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(&panorama_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::Sampler(&sampler),
+                },
+            ],
+        });
+        let face_view = texture.create_view(&wgpu::TextureViewDescriptor {
+            label: Some(&format!("Sky Cubemap Face {}", face)),
+            dimension: Some(wgpu::TextureViewDimension::D2),
+            base_array_layer: face,
+            array_layer_count: Some(1),
+            ..Default::default()
+        });
+
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Sky Bake Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: &face_view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            occlusion_query_set: None,
+            timestamp_writes: None,
+        });
+        pass.set_pipeline(&pipeline);
+        pass.set_bind_group(0, &bind_group, &[]);
+        pass.draw(0..3, 0..1);
+    }
+    queue.submit(std::iter::once(encoder.finish()));
+    println!("[skybox] cubemap de {}x{} generado con {}", resolution, resolution, fragment_entry);
+
+    texture
+}
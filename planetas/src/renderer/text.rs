@@ -0,0 +1,291 @@
+use bytemuck::{Pod, Zeroable};
+use font8x8::legacy::{BASIC_LEGACY, LATIN_LEGACY};
+use wgpu::util::DeviceExt;
+
+use super::pipelines::ShaderLibrary;
+
+/// Lado en píxeles de cada glifo de la fuente (antes de escalar).
+pub const GLYPH_SIZE: f32 = 8.0;
+const ATLAS_COLUMNS: u32 = 16;
+/// ASCII imprimible (U+0020-U+007F), Latin-1 (U+00A0-U+00FF) y un bloque sólido
+/// que se usa para dibujar los paneles.
+const GLYPH_COUNT: u32 = 96 + 96 + 1;
+const SOLID_GLYPH: u32 = GLYPH_COUNT - 1;
+const ATLAS_WIDTH: u32 = ATLAS_COLUMNS * 8;
+const ATLAS_HEIGHT: u32 = GLYPH_COUNT.div_ceil(ATLAS_COLUMNS) * 8;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+pub struct TextVertex {
+    pub position: [f32; 2],
+    pub uv: [f32; 2],
+    pub color: [f32; 4],
+}
+
+impl TextVertex {
+    pub fn desc() -> wgpu::VertexBufferLayout<'static> {
+        const ATTRIBUTES: [wgpu::VertexAttribute; 3] =
+            wgpu::vertex_attr_array![0 => Float32x2, 1 => Float32x2, 2 => Float32x4];
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<TextVertex>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &ATTRIBUTES,
+        }
+    }
+}
+
+/// Dibuja texto con una fuente bitmap de 8x8 y rectángulos semitransparentes
+/// encima del frame final. Cada frame se encolan los elementos con
+/// `queue_text`/`queue_rect` y `render` los dibuja y vacía la cola.
+pub struct TextRenderer {
+    pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    output_format: wgpu::TextureFormat,
+    bind_group: wgpu::BindGroup,
+    screen_buffer: wgpu::Buffer,
+    vertex_buffer: wgpu::Buffer,
+    vertices: Vec<TextVertex>,
+}
+
+impl TextRenderer {
+    pub fn new(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        shaders: &ShaderLibrary,
+        output_format: wgpu::TextureFormat,
+    ) -> Self {
+        let atlas = device.create_texture_with_data(
+            queue,
+            &wgpu::TextureDescriptor {
+                label: Some("Font Atlas"),
+                size: wgpu::Extent3d {
+                    width: ATLAS_WIDTH,
+                    height: ATLAS_HEIGHT,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: wgpu::TextureFormat::R8Unorm,
+                usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+                view_formats: &[],
+            },
+            wgpu::util::TextureDataOrder::LayerMajor,
+            &build_atlas(),
+        );
+        let atlas_view = atlas.create_view(&wgpu::TextureViewDescriptor::default());
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Font Sampler"),
+            mag_filter: wgpu::FilterMode::Nearest,
+            min_filter: wgpu::FilterMode::Nearest,
+            ..Default::default()
+        });
+
+        let screen_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Text Screen Buffer"),
+            size: 16,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+            label: Some("text_bind_group_layout"),
+        });
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: screen_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(&atlas_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::Sampler(&sampler),
+                },
+            ],
+            label: Some("text_bind_group"),
+        });
+
+        let pipeline = super::pipelines::create_text_pipeline(device, shaders, &bind_group_layout, output_format);
+
+        Self {
+            pipeline,
+            bind_group_layout,
+            output_format,
+            bind_group,
+            screen_buffer,
+            vertex_buffer: Self::create_vertex_buffer(device, 4096),
+            vertices: Vec::new(),
+        }
+    }
+
+    /// Recrea el pipeline con el código actual de `shaders` (recarga en caliente).
+    pub fn create_pipeline(&self, device: &wgpu::Device, shaders: &ShaderLibrary) -> wgpu::RenderPipeline {
+        super::pipelines::create_text_pipeline(device, shaders, &self.bind_group_layout, self.output_format)
+    }
+
+    pub fn set_pipeline(&mut self, pipeline: wgpu::RenderPipeline) {
+        self.pipeline = pipeline;
+    }
+
+    /// Encola `text` con la esquina superior izquierda en (`x`, `y`) píxeles.
+    /// Los caracteres fuera de Latin-1 se dibujan como `?`.
+    pub fn queue_text(&mut self, text: &str, x: f32, y: f32, scale: f32, color: [f32; 4]) {
+        let size = GLYPH_SIZE * scale;
+        let (x, y) = (x.round(), y.round());
+
+        for (i, c) in text.chars().enumerate() {
+            if c != ' ' {
+                self.push_quad(x + i as f32 * size, y, size, size, glyph_index(c), color);
+            }
+        }
+    }
+
+    /// Encola un rectángulo sólido (por ejemplo el fondo de un panel).
+    pub fn queue_rect(&mut self, x: f32, y: f32, width: f32, height: f32, color: [f32; 4]) {
+        self.push_quad(x.round(), y.round(), width.round(), height.round(), SOLID_GLYPH, color);
+    }
+
+    /// Ancho en píxeles de `text` con la escala dada.
+    pub fn measure(text: &str, scale: f32) -> f32 {
+        text.chars().count() as f32 * GLYPH_SIZE * scale
+    }
+
+    /// Dibuja todo lo encolado sobre `target` y vacía la cola.
+    pub fn render(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
+        target: &wgpu::TextureView,
+        width: u32,
+        height: u32,
+    ) {
+        if self.vertices.is_empty() {
+            return;
+        }
+
+        let needed = (self.vertices.len() * std::mem::size_of::<TextVertex>()) as wgpu::BufferAddress;
+        if needed > self.vertex_buffer.size() {
+            self.vertex_buffer = Self::create_vertex_buffer(device, self.vertices.len().next_power_of_two());
+        }
+        queue.write_buffer(&self.vertex_buffer, 0, bytemuck::cast_slice(&self.vertices));
+        queue.write_buffer(&self.screen_buffer, 0, bytemuck::cast_slice(&[width as f32, height as f32, 0.0, 0.0]));
+
+        {
+            let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("HUD Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: target,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Load,
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                occlusion_query_set: None,
+                timestamp_writes: None,
+            });
+            pass.set_pipeline(&self.pipeline);
+            pass.set_bind_group(0, &self.bind_group, &[]);
+            pass.set_vertex_buffer(0, self.vertex_buffer.slice(..needed));
+            pass.draw(0..self.vertices.len() as u32, 0..1);
+        }
+
+        self.vertices.clear();
+    }
+
+    fn push_quad(&mut self, x: f32, y: f32, width: f32, height: f32, glyph: u32, color: [f32; 4]) {
+        let u0 = (glyph % ATLAS_COLUMNS * 8) as f32 / ATLAS_WIDTH as f32;
+        let v0 = (glyph / ATLAS_COLUMNS * 8) as f32 / ATLAS_HEIGHT as f32;
+        let u1 = u0 + 8.0 / ATLAS_WIDTH as f32;
+        let v1 = v0 + 8.0 / ATLAS_HEIGHT as f32;
+
+        let corner = |px: f32, py: f32, u: f32, v: f32| TextVertex {
+            position: [px, py],
+            uv: [u, v],
+            color,
+        };
+        let top_left = corner(x, y, u0, v0);
+        let top_right = corner(x + width, y, u1, v0);
+        let bottom_left = corner(x, y + height, u0, v1);
+        let bottom_right = corner(x + width, y + height, u1, v1);
+
+        self.vertices.extend_from_slice(&[top_left, bottom_left, top_right, top_right, bottom_left, bottom_right]);
+    }
+
+    fn create_vertex_buffer(device: &wgpu::Device, vertex_capacity: usize) -> wgpu::Buffer {
+        device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Text Vertex Buffer"),
+            size: (vertex_capacity * std::mem::size_of::<TextVertex>()) as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        })
+    }
+}
+
+fn glyph_index(c: char) -> u32 {
+    match c as u32 {
+        code @ 0x20..=0x7F => code - 0x20,
+        code @ 0xA0..=0xFF => 96 + code - 0xA0,
+        _ => '?' as u32 - 0x20,
+    }
+}
+
+/// Rasteriza los glifos de font8x8 (bit 0 = píxel izquierdo) en un atlas de un canal.
+fn build_atlas() -> Vec<u8> {
+    let mut pixels = vec![0u8; (ATLAS_WIDTH * ATLAS_HEIGHT) as usize];
+    let solid = [0xFFu8; 8];
+
+    let glyphs = BASIC_LEGACY[0x20..].iter().chain(LATIN_LEGACY.iter()).chain(std::iter::once(&solid));
+    for (index, rows) in glyphs.enumerate() {
+        let cell_x = index as u32 % ATLAS_COLUMNS * 8;
+        let cell_y = index as u32 / ATLAS_COLUMNS * 8;
+
+        for (row, bits) in rows.iter().enumerate() {
+            for column in 0..8 {
+                if bits & (1 << column) != 0 {
+                    let offset = (cell_y + row as u32) * ATLAS_WIDTH + cell_x + column;
+                    pixels[offset as usize] = 255;
+                }
+            }
+        }
+    }
+
+    pixels
+}
@@ -2,10 +2,24 @@ use bytemuck::{Pod, Zeroable};
 use std::sync::Arc;
 use wgpu::util::DeviceExt;
 
+use super::material::Material;
+use super::model::Model;
+use super::pipelines::ShaderLibrary;
+use super::planets::{BASE_SPHERE_RADIUS, RING_MESH_RADII};
+use super::postprocess::{PostProcess, HDR_FORMAT};
+use super::scene::Scene;
+use super::shadows::{ShadowMap, SHADOW_FAR};
+use super::skybox::SkyCubemap;
+use super::text::TextRenderer;
+
 #[repr(C)]
-#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+#[derive(Copy, Clone, Debug, Default, Pod, Zeroable)]
 pub struct Vertex {
     pub position: [f32; 3],
+    pub normal: [f32; 3],
+    pub uv: [f32; 2],
+    /// Tangente (dirección de +U) con el signo de la bitangente en `w`
+    pub tangent: [f32; 4],
 }
 
 impl Vertex {
@@ -19,6 +33,21 @@ impl Vertex {
                     shader_location: 0,
                     format: wgpu::VertexFormat::Float32x3,
                 },
+                wgpu::VertexAttribute {
+                    offset: 12,
+                    shader_location: 1,
+                    format: wgpu::VertexFormat::Float32x3,
+                },
+                wgpu::VertexAttribute {
+                    offset: 24,
+                    shader_location: 2,
+                    format: wgpu::VertexFormat::Float32x2,
+                },
+                wgpu::VertexAttribute {
+                    offset: 32,
+                    shader_location: 3,
+                    format: wgpu::VertexFormat::Float32x4,
+                },
             ],
         }
     }
@@ -33,14 +62,34 @@ pub struct Uniforms {
     pub _padding: [f32; 3],
 }
 
+/// Uniform de iluminación compartido (grupo 1) por planetas, lunas, anillos, nave y props.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Pod, Zeroable)]
+pub struct LightUniforms {
+    pub sun_position: [f32; 3],
+    pub intensity: f32,
+    pub sun_color: [f32; 3],
+    pub ambient: f32,
+    pub camera_position: [f32; 3],
+    pub shadow_far: f32,
+}
+
 pub struct RendererState {
-    pub surface: wgpu::Surface<'static>,
+    pub surface: Option<wgpu::Surface<'static>>,
+    pub offscreen_texture: Option<wgpu::Texture>,
     pub device: wgpu::Device,
     pub queue: wgpu::Queue,
     pub config: wgpu::SurfaceConfiguration,
+    /// Código de los shaders y layouts con que se crearon los pipelines; la
+    /// recarga en caliente los necesita para volver a crearlos
+    pub shaders: ShaderLibrary,
+    pub uniform_bind_group_layout: wgpu::BindGroupLayout,
+    pub light_bind_group_layout: wgpu::BindGroupLayout,
+    pub material_bind_group_layout: wgpu::BindGroupLayout,
+    pub sky_bind_group_layout: wgpu::BindGroupLayout,
     pub planet_pipelines: Vec<wgpu::RenderPipeline>,
-    pub moon_pipeline: wgpu::RenderPipeline,
     pub ship_pipeline: wgpu::RenderPipeline,
+    pub prop_pipeline: wgpu::RenderPipeline,
     pub skybox_pipeline: wgpu::RenderPipeline,
     pub ring_pipeline: wgpu::RenderPipeline,
     pub orbit_pipeline: wgpu::RenderPipeline,
@@ -60,22 +109,39 @@ pub struct RendererState {
     pub orbit_index_buffer: wgpu::Buffer,
     pub planet_uniform_buffers: Vec<wgpu::Buffer>,
     pub planet_bind_groups: Vec<wgpu::BindGroup>,
-    pub moon_uniform_buffers: Vec<wgpu::Buffer>,
-    pub moon_bind_groups: Vec<wgpu::BindGroup>,
+    pub planet_materials: Vec<Material>,
     pub ring_uniform_buffers: Vec<wgpu::Buffer>,
     pub ring_bind_groups: Vec<wgpu::BindGroup>,
+    pub orbit_uniform_buffers: Vec<wgpu::Buffer>,
     pub orbit_bind_groups: Vec<wgpu::BindGroup>,
     pub orbit_ranges: Vec<(u32, u32)>,
     pub ship_uniform_buffer: wgpu::Buffer,
     pub ship_bind_group: wgpu::BindGroup,
+    /// Modelo de `Scene::ship_model`; si no hay se dibuja la nave hecha a mano
+    pub ship_model: Option<Model>,
+    pub prop_models: Vec<Model>,
+    pub prop_uniform_buffers: Vec<wgpu::Buffer>,
+    pub prop_bind_groups: Vec<wgpu::BindGroup>,
     pub skybox_uniform_buffer: wgpu::Buffer,
     pub skybox_bind_group: wgpu::BindGroup,
+    /// Cubemap del cielo; None si se calcula el cielo procedural en cada frame
+    pub sky: Option<SkyCubemap>,
+    pub light_uniform_buffer: wgpu::Buffer,
+    pub light_bind_group: wgpu::BindGroup,
+    pub shadow_map: ShadowMap,
+    pub shadow_pipeline: wgpu::RenderPipeline,
+    pub post: PostProcess,
+    pub text: TextRenderer,
     pub depth_texture: wgpu::Texture,
     pub depth_view: wgpu::TextureView,
 }
 
 impl RendererState {
-    pub async fn new(window: Arc<winit::window::Window>, size: winit::dpi::PhysicalSize<u32>) -> Self {
+    pub async fn new(
+        window: Arc<winit::window::Window>,
+        size: winit::dpi::PhysicalSize<u32>,
+        scene: &Scene,
+    ) -> Self {
         let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
             backends: wgpu::Backends::PRIMARY,
             ..Default::default()
@@ -89,12 +155,7 @@ impl RendererState {
             force_fallback_adapter: false,
         }).await.unwrap();
 
-        let (device, queue) = adapter.request_device(&wgpu::DeviceDescriptor {
-            required_features: wgpu::Features::empty(),
-            required_limits: wgpu::Limits::default(),
-            label: None,
-            memory_hints: Default::default(),
-        }, None).await.unwrap();
+        let (device, queue) = Self::request_device(&adapter).await;
 
         let surface_caps = surface.get_capabilities(&adapter);
         let surface_format = surface_caps.formats.iter()
@@ -114,11 +175,83 @@ impl RendererState {
         };
         surface.configure(&device, &config);
 
+        Self::build(device, queue, config, Some(surface), size, scene)
+    }
+
+    /// Crea el estado sin ventana: se renderiza a una textura offscreen que luego
+    /// se puede leer con `read_frame`. Si no hay GPU se usa el adaptador de software.
+    pub async fn new_headless(size: winit::dpi::PhysicalSize<u32>, scene: &Scene) -> Self {
+        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
+            backends: wgpu::Backends::all(),
+            ..Default::default()
+        });
+
+        let mut adapter = instance.request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::default(),
+            compatible_surface: None,
+            force_fallback_adapter: false,
+        }).await;
+
+        if adapter.is_none() {
+            println!("[headless] no hay GPU disponible, usando adaptador de software");
+            adapter = instance.request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference: wgpu::PowerPreference::default(),
+                compatible_surface: None,
+                force_fallback_adapter: true,
+            }).await;
+        }
+
+        let adapter = adapter.expect("No se encontró ningún adaptador gráfico");
+        println!("[headless] adaptador: {:?}", adapter.get_info().name);
+
+        let (device, queue) = Self::request_device(&adapter).await;
+
+        let config = wgpu::SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            width: size.width,
+            height: size.height,
+            present_mode: wgpu::PresentMode::Fifo,
+            alpha_mode: wgpu::CompositeAlphaMode::Opaque,
+            view_formats: vec![],
+            desired_maximum_frame_latency: 2,
+        };
+
+        Self::build(device, queue, config, None, size, scene)
+    }
+
+    async fn request_device(adapter: &wgpu::Adapter) -> (wgpu::Device, wgpu::Queue) {
+        adapter.request_device(&wgpu::DeviceDescriptor {
+            // Las texturas KTX2 en formatos BC solo se usan si la GPU los soporta
+            required_features: adapter.features() & wgpu::Features::TEXTURE_COMPRESSION_BC,
+            // Los adaptadores de software (y las GPU viejas) no llegan a los límites
+            // por defecto; basta con los de downlevel y la resolución que admita el adaptador
+            required_limits: wgpu::Limits::downlevel_defaults().using_resolution(adapter.limits()),
+            label: None,
+            memory_hints: Default::default(),
+        }, None).await.unwrap()
+    }
+
+    fn build(
+        device: wgpu::Device,
+        queue: wgpu::Queue,
+        config: wgpu::SurfaceConfiguration,
+        surface: Option<wgpu::Surface<'static>>,
+        size: winit::dpi::PhysicalSize<u32>,
+        scene: &Scene,
+    ) -> Self {
+        let planets = &scene.bodies;
+        let offscreen_texture = if surface.is_none() {
+            Some(Self::create_offscreen_texture(&device, &config))
+        } else {
+            None
+        };
+
         let depth_texture = Self::create_depth_texture(&device, size);
         let depth_view = depth_texture.create_view(&wgpu::TextureViewDescriptor::default());
 
         // Crear geometrías
-        let (vertices, indices) = super::geometry::create_sphere(2.0, 64, 64);
+        let (vertices, indices) = super::geometry::create_sphere(BASE_SPHERE_RADIUS, 64, 64);
         println!("[debug] sphere: verts={} indices={}", vertices.len(), indices.len());
         if !indices.is_empty() {
             println!("[debug] sphere_indices sample: {:?}", &indices[0..indices.len().min(20)]);
@@ -130,7 +263,7 @@ impl RendererState {
         println!("[debug] ship: verts={} indices={}", ship_vertices.len(), ship_indices.len());
         let (skybox_vertices, skybox_indices) = super::skybox::create_skybox();
         println!("[debug] skybox: verts={} indices={}", skybox_vertices.len(), skybox_indices.len());
-        let (ring_vertices, ring_indices) = super::geometry::create_ring(1.0, 2.0, 64);
+        let (ring_vertices, ring_indices) = super::geometry::create_ring(RING_MESH_RADII.0, RING_MESH_RADII.1, 64);
         println!("[debug] ring: verts={} indices={}", ring_vertices.len(), ring_indices.len());
         if !ring_indices.is_empty() {
             println!("[debug] ring_indices sample: {:?}", &ring_indices[0..ring_indices.len().min(20)]);
@@ -197,18 +330,13 @@ impl RendererState {
         let ring_num_indices = ring_indices.len() as u32;
 
         // Crear órbitas para cada planeta
-        let planets = super::planets::create_planet_system();
         let mut orbit_vertices = Vec::new();
         let mut orbit_indices = Vec::new();
         let mut orbit_ranges = Vec::new();
 
-        for planet in &planets {
-            if planet.orbit_radius > 0.0 {
-                let (orbit_verts, orbit_inds) = super::geometry::create_orbit(
-                    planet.orbit_radius,
-                    100,
-                    planet.orbit_inclination,
-                );
+        for planet in planets {
+            if let Some(orbit) = &planet.orbit {
+                let (orbit_verts, orbit_inds) = super::geometry::create_orbit(orbit, 128);
 
                 let base_vertex = orbit_vertices.len() as u32;
                 let base_index = orbit_indices.len() as u32;
@@ -223,7 +351,7 @@ impl RendererState {
                 let count = (orbit_inds.len()) as u32;
                 orbit_ranges.push((base_index, count));
             } else {
-                // Para el sol, no agregar indices (no se renderiza órbita)
+                // Cuerpos sin órbita (el sol): no se renderiza línea
                 orbit_ranges.push((0, 0));
             }
         }
@@ -263,54 +391,123 @@ impl RendererState {
             label: Some("uniform_bind_group_layout"),
         });
 
-        // Crear pipelines
-        let planet_pipelines = super::pipelines::create_all_planet_pipelines(
+        // Layout del uniform de iluminación y el mapa de sombras (grupo 1)
+        let light_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Depth,
+                        view_dimension: wgpu::TextureViewDimension::Cube,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Comparison),
+                    count: None,
+                },
+            ],
+            label: Some("light_bind_group_layout"),
+        });
+
+        // Al arrancar se usan siempre las copias embebidas de los shaders
+        let shaders = ShaderLibrary::default();
+
+        // Mapa de sombras del sol
+        let shadow_map = ShadowMap::new(&device);
+        let shadow_pipeline = super::pipelines::create_shadow_pipeline(
             &device,
+            &shaders,
             &uniform_bind_group_layout,
-            config.format,
+            &shadow_map.face_bind_group_layout,
         );
 
-        let moon_pipeline = super::pipelines::create_moon_pipeline(
+        // Postproceso: la escena se dibuja en un target HDR y se tonemapea al final
+        let post = PostProcess::new(&device, &shaders, size.width, size.height, config.format);
+        let text = TextRenderer::new(&device, &queue, &shaders, config.format);
+
+        // Materiales de planetas y modelos (grupo 2)
+        let material_bind_group_layout = Material::create_bind_group_layout(&device);
+        let material_sampler = Material::create_sampler(&device);
+
+        // Crear pipelines (todas dibujan en el target HDR)
+        let planet_pipelines = super::pipelines::create_all_planet_pipelines(
             &device,
+            &shaders,
             &uniform_bind_group_layout,
-            config.format,
+            &light_bind_group_layout,
+            &material_bind_group_layout,
+            HDR_FORMAT,
         );
 
         let ship_pipeline = super::pipelines::create_ship_pipeline(
             &device,
+            &shaders,
+            &uniform_bind_group_layout,
+            &light_bind_group_layout,
+            HDR_FORMAT,
+        );
+
+        let prop_pipeline = super::pipelines::create_prop_pipeline(
+            &device,
+            &shaders,
             &uniform_bind_group_layout,
-            config.format,
+            &light_bind_group_layout,
+            &material_bind_group_layout,
+            HDR_FORMAT,
         );
 
+        // Cielo en cubemap (grupo 1 del skybox) si la escena lo pide
+        let sky_bind_group_layout = SkyCubemap::create_bind_group_layout(&device);
+        let sky = SkyCubemap::new(&device, &queue, &shaders, &sky_bind_group_layout, &scene.skybox);
+
         let skybox_pipeline = super::pipelines::create_skybox_pipeline(
             &device,
+            &shaders,
             &uniform_bind_group_layout,
-            config.format,
+            sky.as_ref().map(|_| &sky_bind_group_layout),
+            HDR_FORMAT,
         );
 
         let ring_pipeline = super::pipelines::create_ring_pipeline(
             &device,
+            &shaders,
             &uniform_bind_group_layout,
-            config.format,
+            &light_bind_group_layout,
+            HDR_FORMAT,
         );
 
         let orbit_pipeline = super::pipelines::create_orbit_pipeline(
             &device,
+            &shaders,
             &uniform_bind_group_layout,
-            config.format,
+            HDR_FORMAT,
         );
 
         // Crear uniformes y bind groups para planetas
-        let num_planets = planets.len();
         let mut planet_uniform_buffers = Vec::new();
         let mut planet_bind_groups = Vec::new();
-        let mut moon_uniform_buffers = Vec::new();
-        let mut moon_bind_groups = Vec::new();
+        let mut planet_materials = Vec::new();
         let mut ring_uniform_buffers = Vec::new();
         let mut ring_bind_groups = Vec::new();
+        let mut orbit_uniform_buffers = Vec::new();
         let mut orbit_bind_groups = Vec::new();
 
-        for i in 0..num_planets {
+        for (i, planet) in planets.iter().enumerate() {
             let uniforms = Uniforms {
                 view_proj: glam::Mat4::IDENTITY.to_cols_array_2d(),
                 model: glam::Mat4::IDENTITY.to_cols_array_2d(),
@@ -336,25 +533,14 @@ impl RendererState {
 
             planet_uniform_buffers.push(planet_uniform_buffer);
             planet_bind_groups.push(planet_bind_group);
-
-            // Moon uniform
-            let moon_uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-                label: Some(&format!("Moon Uniform Buffer {}", i)),
-                contents: bytemuck::cast_slice(&[uniforms]),
-                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
-            });
-
-            let moon_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-                layout: &uniform_bind_group_layout,
-                entries: &[wgpu::BindGroupEntry {
-                    binding: 0,
-                    resource: moon_uniform_buffer.as_entire_binding(),
-                }],
-                label: Some(&format!("moon_bind_group {}", i)),
-            });
-
-            moon_uniform_buffers.push(moon_uniform_buffer);
-            moon_bind_groups.push(moon_bind_group);
+            planet_materials.push(Material::new(
+                &device,
+                &queue,
+                &material_bind_group_layout,
+                &material_sampler,
+                &planet.name,
+                planet.material.as_ref(),
+            ));
 
             // Ring uniform
             let ring_uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
@@ -375,16 +561,23 @@ impl RendererState {
             ring_uniform_buffers.push(ring_uniform_buffer);
             ring_bind_groups.push(ring_bind_group);
 
-            // Orbit bind group (usa el mismo buffer que el planeta)
+            // Orbit uniform (model = marco del padre)
+            let orbit_uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some(&format!("Orbit Uniform Buffer {}", i)),
+                contents: bytemuck::cast_slice(&[uniforms]),
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            });
+
             let orbit_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
                 layout: &uniform_bind_group_layout,
                 entries: &[wgpu::BindGroupEntry {
                     binding: 0,
-                    resource: planet_uniform_buffers[i].as_entire_binding(),
+                    resource: orbit_uniform_buffer.as_entire_binding(),
                 }],
                 label: Some(&format!("orbit_bind_group {}", i)),
             });
 
+            orbit_uniform_buffers.push(orbit_uniform_buffer);
             orbit_bind_groups.push(orbit_bind_group);
         }
 
@@ -409,6 +602,43 @@ impl RendererState {
             label: Some("ship_bind_group"),
         });
 
+        // Modelos importados: la nave de la escena usa los uniformes de la nave
+        let ship_model = scene.ship_model.as_ref().and_then(|settings| settings.mesh.as_ref()).map(|mesh| {
+            Model::new(&device, &queue, &material_bind_group_layout, &material_sampler, "Ship Model", mesh)
+        });
+
+        let mut prop_models = Vec::new();
+        let mut prop_uniform_buffers = Vec::new();
+        let mut prop_bind_groups = Vec::new();
+
+        for (i, prop) in scene.props.iter().enumerate() {
+            let mesh = prop.model.mesh.as_ref().expect("las mallas se cargan con la escena");
+            prop_models.push(Model::new(&device, &queue, &material_bind_group_layout, &material_sampler, &prop.name, mesh));
+
+            let prop_uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some(&format!("Prop Uniform Buffer {}", i)),
+                contents: bytemuck::cast_slice(&[Uniforms {
+                    view_proj: glam::Mat4::IDENTITY.to_cols_array_2d(),
+                    model: glam::Mat4::IDENTITY.to_cols_array_2d(),
+                    time: 0.0,
+                    _padding: [0.0; 3],
+                }]),
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            });
+
+            let prop_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                layout: &uniform_bind_group_layout,
+                entries: &[wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: prop_uniform_buffer.as_entire_binding(),
+                }],
+                label: Some(&format!("prop_bind_group {}", i)),
+            });
+
+            prop_uniform_buffers.push(prop_uniform_buffer);
+            prop_bind_groups.push(prop_bind_group);
+        }
+
         // Uniformes de skybox
         let skybox_uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: Some("Skybox Uniform Buffer"),
@@ -430,14 +660,53 @@ impl RendererState {
             label: Some("skybox_bind_group"),
         });
 
+        // Uniforme de iluminación
+        let light_uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Light Uniform Buffer"),
+            contents: bytemuck::cast_slice(&[LightUniforms {
+                sun_position: [0.0; 3],
+                intensity: 1.0,
+                sun_color: [1.0; 3],
+                ambient: 0.15,
+                camera_position: [0.0; 3],
+                shadow_far: SHADOW_FAR,
+            }]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let light_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &light_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: light_uniform_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(&shadow_map.cube_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::Sampler(&shadow_map.sampler),
+                },
+            ],
+            label: Some("light_bind_group"),
+        });
+
         Self {
             surface,
+            offscreen_texture,
             device,
             queue,
             config,
+            shaders,
+            uniform_bind_group_layout,
+            light_bind_group_layout,
+            material_bind_group_layout,
+            sky_bind_group_layout,
             planet_pipelines,
-            moon_pipeline,
             ship_pipeline,
+            prop_pipeline,
             skybox_pipeline,
             ring_pipeline,
             orbit_pipeline,
@@ -458,15 +727,26 @@ impl RendererState {
             orbit_ranges,
             planet_uniform_buffers,
             planet_bind_groups,
-            moon_uniform_buffers,
-            moon_bind_groups,
+            planet_materials,
             ring_uniform_buffers,
             ring_bind_groups,
+            orbit_uniform_buffers,
             orbit_bind_groups,
             ship_uniform_buffer,
             ship_bind_group,
+            ship_model,
+            prop_models,
+            prop_uniform_buffers,
+            prop_bind_groups,
             skybox_uniform_buffer,
             skybox_bind_group,
+            sky,
+            light_uniform_buffer,
+            light_bind_group,
+            shadow_map,
+            shadow_pipeline,
+            post,
+            text,
             depth_texture,
             depth_view,
         }
@@ -475,12 +755,93 @@ impl RendererState {
     pub fn resize(&mut self, new_size: winit::dpi::PhysicalSize<u32>) {
         self.config.width = new_size.width;
         self.config.height = new_size.height;
-        self.surface.configure(&self.device, &self.config);
-        
+        match &self.surface {
+            Some(surface) => surface.configure(&self.device, &self.config),
+            None => {
+                self.offscreen_texture = Some(Self::create_offscreen_texture(&self.device, &self.config));
+            }
+        }
+
+        self.post.resize(&self.device, new_size.width, new_size.height);
         self.depth_texture = Self::create_depth_texture(&self.device, new_size);
         self.depth_view = self.depth_texture.create_view(&wgpu::TextureViewDescriptor::default());
     }
 
+    /// Copia la textura offscreen a memoria y devuelve los píxeles RGBA8 (sin padding por fila).
+    pub fn read_frame(&self) -> Option<Vec<u8>> {
+        let texture = self.offscreen_texture.as_ref()?;
+        let width = self.config.width;
+        let height = self.config.height;
+
+        let unpadded_bytes_per_row = width * 4;
+        let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+        let padded_bytes_per_row = unpadded_bytes_per_row.div_ceil(align) * align;
+
+        let readback_buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Readback Buffer"),
+            size: (padded_bytes_per_row * height) as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Readback Encoder"),
+        });
+        encoder.copy_texture_to_buffer(
+            wgpu::ImageCopyTexture {
+                texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::ImageCopyBuffer {
+                buffer: &readback_buffer,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_bytes_per_row),
+                    rows_per_image: Some(height),
+                },
+            },
+            wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+        );
+        self.queue.submit(std::iter::once(encoder.finish()));
+
+        let slice = readback_buffer.slice(..);
+        slice.map_async(wgpu::MapMode::Read, |result| result.unwrap());
+        self.device.poll(wgpu::Maintain::Wait);
+
+        let data = slice.get_mapped_range();
+        let mut pixels = Vec::with_capacity((unpadded_bytes_per_row * height) as usize);
+        for row in data.chunks(padded_bytes_per_row as usize) {
+            pixels.extend_from_slice(&row[..unpadded_bytes_per_row as usize]);
+        }
+        drop(data);
+        readback_buffer.unmap();
+
+        Some(pixels)
+    }
+
+    fn create_offscreen_texture(device: &wgpu::Device, config: &wgpu::SurfaceConfiguration) -> wgpu::Texture {
+        device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Offscreen Texture"),
+            size: wgpu::Extent3d {
+                width: config.width,
+                height: config.height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: config.format,
+            usage: config.usage,
+            view_formats: &[],
+        })
+    }
+
     fn create_depth_texture(device: &wgpu::Device, size: winit::dpi::PhysicalSize<u32>) -> wgpu::Texture {
         device.create_texture(&wgpu::TextureDescriptor {
             label: Some("Depth Texture"),
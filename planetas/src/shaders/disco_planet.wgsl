@@ -9,12 +9,18 @@ var<uniform> uniforms: Uniforms;
 
 struct VertexInput {
     @location(0) position: vec3<f32>,
+    @location(1) normal: vec3<f32>,
+    @location(2) uv: vec2<f32>,
+    @location(3) tangent: vec4<f32>,
 };
 
 struct VertexOutput {
     @builtin(position) clip_position: vec4<f32>,
     @location(0) world_pos: vec3<f32>,
     @location(1) normal: vec3<f32>,
+    @location(2) world_normal: vec3<f32>,
+    @location(3) uv: vec2<f32>,
+    @location(4) world_tangent: vec4<f32>,
 };
 
 @vertex
@@ -23,18 +29,22 @@ fn vs_main(input: VertexInput) -> VertexOutput {
     let world_pos = uniforms.model * vec4<f32>(input.position, 1.0);
     out.world_pos = world_pos.xyz;
     out.clip_position = uniforms.view_proj * world_pos;
-    out.normal = normalize(input.position);
+    out.normal = input.normal;
+    out.world_normal = normalize((uniforms.model * vec4<f32>(out.normal, 0.0)).xyz);
+    out.uv = input.uv;
+    out.world_tangent = vec4<f32>((uniforms.model * vec4<f32>(input.tangent.xyz, 0.0)).xyz, input.tangent.w);
     return out;
 }
 
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     let normal = normalize(in.normal);
+    let surface = sample_material(in.uv, in.world_normal, in.world_tangent);
     let time_scale = uniforms.time;
     
     // Crear patrón de bola de discoteca (espejos cuadrados)
-    let phi = atan2(normal.z, normal.x);
-    let theta = acos(normal.y);
+    let phi = in.uv.x * 6.2831853;
+    let theta = in.uv.y * 3.1415927;
     
     let mirror_size = 0.3;
     let grid_x = floor(phi * 8.0 / mirror_size);
@@ -58,20 +68,20 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     // Añadir reflejos de colores
     color = color * (0.6 + light_color * 0.4);
     
-    // Iluminación
-    let light_dir = normalize(vec3<f32>(1.0, 0.7, 1.0));
-    let diff = max(dot(normal, light_dir), 0.0);
-    color = color * (0.4 + diff * 0.6);
+    // Textura de albedo del material
+    color = material_albedo(color, surface);
     
-    // Especular brillante (efecto espejo)
-    let view_dir = normalize(vec3<f32>(0.0, 0.0, 1.0));
-    let reflect_dir = reflect(-light_dir, normal);
-    let spec = pow(max(dot(view_dir, reflect_dir), 0.0), 64.0);
-    color = color + vec3<f32>(1.0, 1.0, 1.0) * spec * 0.8;
+    // Iluminación desde el sol con especular brillante (efecto espejo)
+    let world_normal = surface.normal;
+    let lit = sun_light(in.world_pos, world_normal, 64.0);
+    color = shade(color, lit, 0.8 * surface.specular);
     
     // Brillo pulsante
     let pulse = sin(time_scale * 3.0) * 0.1 + 0.9;
     color = color * pulse;
     
+    // Emisión del material
+    color = color + surface.emissive;
+    
     return vec4<f32>(color, 1.0);
 }
\ No newline at end of file
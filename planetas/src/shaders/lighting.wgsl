@@ -0,0 +1,64 @@
+// Iluminación compartida: el sol es una luz puntual en la posición del cuerpo Sun.
+// Este archivo se antepone a los shaders de planetas, lunas, anillos y nave.
+struct Light {
+    sun_position: vec3<f32>,
+    intensity: f32,
+    sun_color: vec3<f32>,
+    ambient: f32,
+    camera_position: vec3<f32>,
+    shadow_far: f32,
+};
+
+@group(1) @binding(0)
+var<uniform> light: Light;
+
+@group(1) @binding(1)
+var shadow_map: texture_depth_cube;
+
+@group(1) @binding(2)
+var shadow_sampler: sampler_comparison;
+
+// 1 iluminado, 0 en sombra. El cubemap se indexa con la dirección sol -> fragmento
+// y guarda la distancia lineal al sol dividida por `shadow_far`.
+fn sun_shadow(world_pos: vec3<f32>, normal: vec3<f32>) -> f32 {
+    let from_sun = world_pos - light.sun_position;
+    let distance = length(from_sun);
+
+    // Desplazar hacia el sol a lo largo de la normal para evitar acné de sombra.
+    // El desplazamiento crece con la distancia igual que el tamaño de un texel.
+    let n = select(normal, -normal, dot(normal, from_sun) > 0.0);
+    let to_frag = from_sun + n * (0.05 + distance * 0.006);
+
+    let depth = length(to_frag) / light.shadow_far - 0.0005;
+    return textureSampleCompareLevel(shadow_map, shadow_sampler, to_frag, depth);
+}
+
+struct SunLight {
+    diffuse: vec3<f32>,
+    specular: vec3<f32>,
+    // 1 en el lado de día, 0 en el de noche, con transición suave en el terminador
+    day: f32,
+    view_dir: vec3<f32>,
+};
+
+fn sun_light(world_pos: vec3<f32>, normal: vec3<f32>, shininess: f32) -> SunLight {
+    let to_sun = normalize(light.sun_position - world_pos);
+    let view_dir = normalize(light.camera_position - world_pos);
+    let n_dot_l = dot(normal, to_sun);
+    let day = smoothstep(-0.08, 0.15, n_dot_l);
+
+    let radiance = light.sun_color * light.intensity * sun_shadow(world_pos, normal);
+    let half_dir = normalize(to_sun + view_dir);
+    let spec = pow(max(dot(normal, half_dir), 0.0), shininess) * day;
+
+    var lit: SunLight;
+    lit.diffuse = radiance * max(n_dot_l, 0.0);
+    lit.specular = radiance * spec;
+    lit.day = day;
+    lit.view_dir = view_dir;
+    return lit;
+}
+
+fn shade(albedo: vec3<f32>, lit: SunLight, spec_strength: f32) -> vec3<f32> {
+    return albedo * (vec3<f32>(light.ambient) + lit.diffuse) + lit.specular * spec_strength;
+}
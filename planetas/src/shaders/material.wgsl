@@ -0,0 +1,59 @@
+// Material del cuerpo: texturas que se mezclan con el patrón procedural.
+// Este archivo se antepone (después de lighting.wgsl) a los shaders de planetas
+// y lunas. Sin material los mapas son neutros y el resultado no cambia.
+struct MaterialParams {
+    // 0 solo el color procedural, 1 solo la textura (por el alfa del albedo)
+    albedo_mix: f32,
+    normal_strength: f32,
+    emissive_strength: f32,
+    specular_strength: f32,
+};
+
+@group(2) @binding(0)
+var<uniform> material: MaterialParams;
+
+@group(2) @binding(1)
+var albedo_map: texture_2d<f32>;
+
+@group(2) @binding(2)
+var normal_map: texture_2d<f32>;
+
+@group(2) @binding(3)
+var emissive_map: texture_2d<f32>;
+
+@group(2) @binding(4)
+var specular_map: texture_2d<f32>;
+
+@group(2) @binding(5)
+var material_sampler: sampler;
+
+// Muestrea todos los mapas de una vez (en control de flujo uniforme) y devuelve
+// la normal del mundo ya perturbada por el mapa de normales.
+struct MaterialSample {
+    albedo: vec4<f32>,
+    normal: vec3<f32>,
+    emissive: vec3<f32>,
+    specular: f32,
+};
+
+fn sample_material(uv: vec2<f32>, world_normal: vec3<f32>, world_tangent: vec4<f32>) -> MaterialSample {
+    var m: MaterialSample;
+    m.albedo = textureSample(albedo_map, material_sampler, uv);
+    m.emissive = textureSample(emissive_map, material_sampler, uv).rgb * material.emissive_strength;
+    m.specular = textureSample(specular_map, material_sampler, uv).r * material.specular_strength;
+
+    // Espacio tangente: T hacia +U, B hacia -V (arriba en la imagen), N fuera
+    let n = normalize(world_normal);
+    let t = normalize(world_tangent.xyz - n * dot(n, world_tangent.xyz));
+    let b = -cross(n, t) * world_tangent.w;
+    let tangent_normal = textureSample(normal_map, material_sampler, uv).xyz * 2.0 - 1.0;
+    let strength = material.normal_strength;
+    m.normal = normalize(
+        t * (tangent_normal.x * strength) + b * (tangent_normal.y * strength) + n * max(tangent_normal.z, 0.001)
+    );
+    return m;
+}
+
+fn material_albedo(procedural: vec3<f32>, m: MaterialSample) -> vec3<f32> {
+    return mix(procedural, m.albedo.rgb, material.albedo_mix * m.albedo.a);
+}
@@ -9,12 +9,18 @@ var<uniform> uniforms: Uniforms;
 
 struct VertexInput {
     @location(0) position: vec3<f32>,
+    @location(1) normal: vec3<f32>,
+    @location(2) uv: vec2<f32>,
+    @location(3) tangent: vec4<f32>,
 };
 
 struct VertexOutput {
     @builtin(position) clip_position: vec4<f32>,
     @location(0) world_pos: vec3<f32>,
     @location(1) normal: vec3<f32>,
+    @location(2) world_normal: vec3<f32>,
+    @location(3) uv: vec2<f32>,
+    @location(4) world_tangent: vec4<f32>,
 };
 
 @vertex
@@ -23,13 +29,17 @@ fn vs_main(input: VertexInput) -> VertexOutput {
     let world_pos = uniforms.model * vec4<f32>(input.position, 1.0);
     out.world_pos = world_pos.xyz;
     out.clip_position = uniforms.view_proj * world_pos;
-    out.normal = normalize(input.position);
+    out.normal = input.normal;
+    out.world_normal = normalize((uniforms.model * vec4<f32>(out.normal, 0.0)).xyz);
+    out.uv = input.uv;
+    out.world_tangent = vec4<f32>((uniforms.model * vec4<f32>(input.tangent.xyz, 0.0)).xyz, input.tangent.w);
     return out;
 }
 
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     let normal = normalize(in.normal);
+    let surface = sample_material(in.uv, in.world_normal, in.world_tangent);
     
     // Superficie de la luna con colores pastel de fiesta
     let base_color = vec3<f32>(0.85, 0.75, 0.95);  // Lavanda suave
@@ -44,15 +54,20 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     let magic_glow = sin(uniforms.time * 2.0) * 0.1 + 0.9;
     color = color * magic_glow;
     
-    // Iluminación
-    let light_dir = normalize(vec3<f32>(1.0, 0.5, 1.0));
-    let diff = max(dot(normal, light_dir), 0.0);
-    color = color * (0.4 + diff * 0.6);
+    // Textura de albedo del material
+    color = material_albedo(color, surface);
+    
+    // Iluminación desde el sol
+    let world_normal = surface.normal;
+    let lit = sun_light(in.world_pos, world_normal, 8.0);
+    color = shade(color, lit, 0.1 * surface.specular);
     
     // Brillo sutil en los bordes
-    let view_dir = normalize(vec3<f32>(0.0, 0.0, 1.0));
-    let fresnel = pow(1.0 - abs(dot(normal, view_dir)), 3.0);
+    let fresnel = pow(1.0 - abs(dot(world_normal, lit.view_dir)), 3.0);
     color = color + vec3<f32>(0.9, 0.8, 1.0) * fresnel * 0.3;
     
+    // Emisión del material
+    color = color + surface.emissive;
+    
     return vec4<f32>(color, 1.0);
 }
\ No newline at end of file
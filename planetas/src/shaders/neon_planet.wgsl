@@ -9,12 +9,18 @@ var<uniform> uniforms: Uniforms;
 
 struct VertexInput {
     @location(0) position: vec3<f32>,
+    @location(1) normal: vec3<f32>,
+    @location(2) uv: vec2<f32>,
+    @location(3) tangent: vec4<f32>,
 };
 
 struct VertexOutput {
     @builtin(position) clip_position: vec4<f32>,
     @location(0) world_pos: vec3<f32>,
     @location(1) normal: vec3<f32>,
+    @location(2) world_normal: vec3<f32>,
+    @location(3) uv: vec2<f32>,
+    @location(4) world_tangent: vec4<f32>,
 };
 
 @vertex
@@ -23,18 +29,22 @@ fn vs_main(input: VertexInput) -> VertexOutput {
     let world_pos = uniforms.model * vec4<f32>(input.position, 1.0);
     out.world_pos = world_pos.xyz;
     out.clip_position = uniforms.view_proj * world_pos;
-    out.normal = normalize(input.position);
+    out.normal = input.normal;
+    out.world_normal = normalize((uniforms.model * vec4<f32>(out.normal, 0.0)).xyz);
+    out.uv = input.uv;
+    out.world_tangent = vec4<f32>((uniforms.model * vec4<f32>(input.tangent.xyz, 0.0)).xyz, input.tangent.w);
     return out;
 }
 
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     let normal = normalize(in.normal);
+    let surface = sample_material(in.uv, in.world_normal, in.world_tangent);
     let time_scale = uniforms.time;
     
     // Líneas neón en espiral
-    let phi = atan2(normal.z, normal.x);
-    let theta = acos(normal.y);
+    let phi = in.uv.x * 6.2831853;
+    let theta = in.uv.y * 3.1415927;
     
     let spiral = sin((phi * 15.0 + theta * 20.0 - time_scale * 4.0));
     let grid = sin(phi * 30.0) * sin(theta * 30.0);
@@ -61,18 +71,26 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     let energy_pulse = sin(time_scale * 5.0 + length(normal) * 10.0) * 0.2 + 0.8;
     color = color * energy_pulse;
     
-    // Iluminación
-    let light_dir = normalize(vec3<f32>(1.0, 0.5, 1.0));
-    let diff = max(dot(normal, light_dir), 0.0);
-    color = color * (0.4 + diff * 0.6);
+    // Textura de albedo del material
+    color = material_albedo(color, surface);
+    
+    // Iluminación desde el sol
+    let world_normal = surface.normal;
+    let lit = sun_light(in.world_pos, world_normal, 32.0);
+    color = shade(color, lit, 0.5 * surface.specular);
+    
+    // Los tubos neón emiten luz propia (también en el lado de noche)
+    color = color + purple_glow * neon_tubes * energy_pulse * 1.2;
     
     // Brillo neón intenso
-    let view_dir = normalize(vec3<f32>(0.0, 0.0, 1.0));
-    let fresnel = pow(1.0 - abs(dot(normal, view_dir)), 2.0);
+    let fresnel = pow(1.0 - abs(dot(world_normal, lit.view_dir)), 2.0);
     color = color + hot_pink * fresnel * 0.7;
     
     // Aumentar brillo general
     color = color * 1.3;
     
+    // Emisión del material
+    color = color + surface.emissive;
+    
     return vec4<f32>(color, 1.0);
 }
\ No newline at end of file
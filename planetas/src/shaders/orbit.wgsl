@@ -18,7 +18,8 @@ struct VertexOutput {
 @vertex
 fn vs_main(input: VertexInput) -> VertexOutput {
     var out: VertexOutput;
-    let world_pos = vec4<f32>(input.position, 1.0);
+    // model = marco del cuerpo padre, para que las órbitas de las lunas lo sigan
+    let world_pos = uniforms.model * vec4<f32>(input.position, 1.0);
     out.clip_position = uniforms.view_proj * world_pos;
     return out;
 }
@@ -9,12 +9,18 @@ var<uniform> uniforms: Uniforms;
 
 struct VertexInput {
     @location(0) position: vec3<f32>,
+    @location(1) normal: vec3<f32>,
+    @location(2) uv: vec2<f32>,
+    @location(3) tangent: vec4<f32>,
 };
 
 struct VertexOutput {
     @builtin(position) clip_position: vec4<f32>,
     @location(0) world_pos: vec3<f32>,
     @location(1) normal: vec3<f32>,
+    @location(2) world_normal: vec3<f32>,
+    @location(3) uv: vec2<f32>,
+    @location(4) world_tangent: vec4<f32>,
 };
 
 @vertex
@@ -23,7 +29,10 @@ fn vs_main(input: VertexInput) -> VertexOutput {
     let world_pos = uniforms.model * vec4<f32>(input.position, 1.0);
     out.world_pos = world_pos.xyz;
     out.clip_position = uniforms.view_proj * world_pos;
-    out.normal = normalize(input.position);
+    out.normal = input.normal;
+    out.world_normal = normalize((uniforms.model * vec4<f32>(out.normal, 0.0)).xyz);
+    out.uv = input.uv;
+    out.world_tangent = vec4<f32>((uniforms.model * vec4<f32>(input.tangent.xyz, 0.0)).xyz, input.tangent.w);
     return out;
 }
 
@@ -92,6 +101,7 @@ fn snoise(v: vec3<f32>) -> f32 {
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     let normal = normalize(in.normal);
+    let surface = sample_material(in.uv, in.world_normal, in.world_tangent);
     let time_scale = uniforms.time * 2.0;
     let pos = normal * 3.0 + vec3<f32>(time_scale, time_scale * 0.7, time_scale * 0.5);
     
@@ -125,10 +135,19 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     let pulse = sin(uniforms.time * 4.0) * 0.2 + 1.2;
     color = color * pulse;
     
-    // Corona brillante
-    let view_dir = normalize(vec3<f32>(0.0, 0.0, 1.0));
-    let fresnel = pow(1.0 - abs(dot(normal, view_dir)), 2.0);
+    // Textura de albedo del material
+    color = material_albedo(color, surface);
+    
+    // Corona brillante (el sol es emisivo: no recibe iluminación)
+    let view_dir = normalize(light.camera_position - in.world_pos);
+    let fresnel = pow(1.0 - abs(dot(surface.normal, view_dir)), 2.0);
     color = color + color * fresnel * 1.0;
     
+    // Emisión HDR: por encima de 1.0 para que alimente el bloom
+    color = color * 2.5;
+    
+    // Emisión del material
+    color = color + surface.emissive;
+    
     return vec4<f32>(color, 1.0);
 }
\ No newline at end of file
@@ -0,0 +1,162 @@
+// Postproceso HDR: bloom por cadena de mips (prefiltro + downsample + upsample
+// aditivo) y composición final con tonemapping hacia el swapchain.
+struct PostUniforms {
+    exposure: f32,
+    bloom_strength: f32,
+    bloom_threshold: f32,
+    // 0 = ACES, 1 = Reinhard, 2 = AgX
+    tonemapper: u32,
+    // 1 si el destino no es sRGB y hay que codificar la gamma a mano
+    encode_srgb: u32,
+};
+
+@group(0) @binding(0)
+var source: texture_2d<f32>;
+
+@group(0) @binding(1)
+var linear_sampler: sampler;
+
+@group(0) @binding(2)
+var<uniform> post: PostUniforms;
+
+// Solo en la composición: resultado del bloom (mip 0 de la cadena)
+@group(0) @binding(3)
+var bloom: texture_2d<f32>;
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+};
+
+// Triángulo que cubre toda la pantalla, sin vertex buffer
+@vertex
+fn vs_fullscreen(@builtin(vertex_index) index: u32) -> VertexOutput {
+    let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0, 0.0, 1.0);
+    out.uv = uv;
+    return out;
+}
+
+// Downsample de 4 muestras bilineales (promedia un bloque de 4x4 texels)
+fn downsample(uv: vec2<f32>) -> vec3<f32> {
+    let texel = 1.0 / vec2<f32>(textureDimensions(source));
+    let a = textureSampleLevel(source, linear_sampler, uv + texel * vec2<f32>(-1.0, -1.0), 0.0).rgb;
+    let b = textureSampleLevel(source, linear_sampler, uv + texel * vec2<f32>(1.0, -1.0), 0.0).rgb;
+    let c = textureSampleLevel(source, linear_sampler, uv + texel * vec2<f32>(-1.0, 1.0), 0.0).rgb;
+    let d = textureSampleLevel(source, linear_sampler, uv + texel * vec2<f32>(1.0, 1.0), 0.0).rgb;
+    return (a + b + c + d) * 0.25;
+}
+
+fn luminance(color: vec3<f32>) -> f32 {
+    return dot(color, vec3<f32>(0.2126, 0.7152, 0.0722));
+}
+
+// Primer nivel: solo pasa lo que supera el umbral, con una rodilla suave
+@fragment
+fn fs_prefilter(in: VertexOutput) -> @location(0) vec4<f32> {
+    let color = min(downsample(in.uv), vec3<f32>(64.0));
+    let brightness = max(color.r, max(color.g, color.b));
+    let knee = post.bloom_threshold * 0.5;
+    var soft = clamp(brightness - post.bloom_threshold + knee, 0.0, 2.0 * knee);
+    soft = soft * soft / (4.0 * knee + 1e-4);
+    let contribution = max(soft, brightness - post.bloom_threshold) / max(brightness, 1e-4);
+    return vec4<f32>(color * contribution, 1.0);
+}
+
+@fragment
+fn fs_downsample(in: VertexOutput) -> @location(0) vec4<f32> {
+    return vec4<f32>(downsample(in.uv), 1.0);
+}
+
+// Upsample con filtro tienda 3x3; se suma (blend aditivo) al nivel superior
+@fragment
+fn fs_upsample(in: VertexOutput) -> @location(0) vec4<f32> {
+    let texel = 1.0 / vec2<f32>(textureDimensions(source));
+    var color = textureSampleLevel(source, linear_sampler, in.uv, 0.0).rgb * 4.0;
+    color += textureSampleLevel(source, linear_sampler, in.uv + texel * vec2<f32>(-1.0, 0.0), 0.0).rgb * 2.0;
+    color += textureSampleLevel(source, linear_sampler, in.uv + texel * vec2<f32>(1.0, 0.0), 0.0).rgb * 2.0;
+    color += textureSampleLevel(source, linear_sampler, in.uv + texel * vec2<f32>(0.0, -1.0), 0.0).rgb * 2.0;
+    color += textureSampleLevel(source, linear_sampler, in.uv + texel * vec2<f32>(0.0, 1.0), 0.0).rgb * 2.0;
+    color += textureSampleLevel(source, linear_sampler, in.uv + texel * vec2<f32>(-1.0, -1.0), 0.0).rgb;
+    color += textureSampleLevel(source, linear_sampler, in.uv + texel * vec2<f32>(1.0, -1.0), 0.0).rgb;
+    color += textureSampleLevel(source, linear_sampler, in.uv + texel * vec2<f32>(-1.0, 1.0), 0.0).rgb;
+    color += textureSampleLevel(source, linear_sampler, in.uv + texel * vec2<f32>(1.0, 1.0), 0.0).rgb;
+    return vec4<f32>(color / 16.0, 1.0);
+}
+
+// Aproximación de ACES de Krzysztof Narkowicz
+fn tonemap_aces(x: vec3<f32>) -> vec3<f32> {
+    let a = 2.51;
+    let b = 0.03;
+    let c = 2.43;
+    let d = 0.59;
+    let e = 0.14;
+    return clamp((x * (a * x + b)) / (x * (c * x + d) + e), vec3<f32>(0.0), vec3<f32>(1.0));
+}
+
+// Reinhard sobre la luminancia (conserva el tono de los colores saturados)
+fn tonemap_reinhard(x: vec3<f32>) -> vec3<f32> {
+    let l = luminance(x);
+    return clamp(x / (1.0 + l), vec3<f32>(0.0), vec3<f32>(1.0));
+}
+
+// AgX (ajuste polinómico del look por defecto)
+fn agx_contrast(x: vec3<f32>) -> vec3<f32> {
+    let x2 = x * x;
+    let x4 = x2 * x2;
+    return 15.5 * x4 * x2 - 40.14 * x4 * x + 31.96 * x4 - 6.868 * x2 * x + 0.4298 * x2 + 0.1191 * x - 0.00232;
+}
+
+fn tonemap_agx(x: vec3<f32>) -> vec3<f32> {
+    let agx_in = mat3x3<f32>(
+        vec3<f32>(0.842479062253094, 0.0423282422610123, 0.0423756549057051),
+        vec3<f32>(0.0784335999999992, 0.878468636469772, 0.0784336),
+        vec3<f32>(0.0792237451477643, 0.0791661274605434, 0.879142973793104),
+    );
+    let agx_out = mat3x3<f32>(
+        vec3<f32>(1.19687900512017, -0.0528968517574562, -0.0529716355144438),
+        vec3<f32>(-0.0980208811401368, 1.15190312990417, -0.0980434501171241),
+        vec3<f32>(-0.0990297440797205, -0.0989611768448433, 1.15107367264116),
+    );
+    let min_ev = -12.47393;
+    let max_ev = 4.026069;
+
+    var color = agx_in * max(x, vec3<f32>(1e-10));
+    color = clamp(log2(color), vec3<f32>(min_ev), vec3<f32>(max_ev));
+    color = (color - min_ev) / (max_ev - min_ev);
+    color = agx_out * agx_contrast(color);
+    // El polinomio devuelve valores codificados para pantalla: volver a lineal
+    return pow(clamp(color, vec3<f32>(0.0), vec3<f32>(1.0)), vec3<f32>(2.2));
+}
+
+fn linear_to_srgb(c: vec3<f32>) -> vec3<f32> {
+    let low = c * 12.92;
+    let high = 1.055 * pow(c, vec3<f32>(1.0 / 2.4)) - 0.055;
+    return select(high, low, c <= vec3<f32>(0.0031308));
+}
+
+@fragment
+fn fs_composite(in: VertexOutput) -> @location(0) vec4<f32> {
+    let hdr = textureSampleLevel(source, linear_sampler, in.uv, 0.0).rgb;
+    let glow = textureSampleLevel(bloom, linear_sampler, in.uv, 0.0).rgb;
+    let color = (hdr + glow * post.bloom_strength) * post.exposure;
+
+    var mapped: vec3<f32>;
+    switch post.tonemapper {
+        case 1u: {
+            mapped = tonemap_reinhard(color);
+        }
+        case 2u: {
+            mapped = tonemap_agx(color);
+        }
+        default: {
+            mapped = tonemap_aces(color);
+        }
+    }
+
+    if (post.encode_srgb == 1u) {
+        mapped = linear_to_srgb(mapped);
+    }
+    return vec4<f32>(mapped, 1.0);
+}
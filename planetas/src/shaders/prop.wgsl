@@ -0,0 +1,54 @@
+// Modelos importados (glTF/OBJ): el color sale solo del material, sin patrón procedural.
+struct Uniforms {
+    view_proj: mat4x4<f32>,
+    model: mat4x4<f32>,
+    time: f32,
+};
+
+@group(0) @binding(0)
+var<uniform> uniforms: Uniforms;
+
+struct VertexInput {
+    @location(0) position: vec3<f32>,
+    @location(1) normal: vec3<f32>,
+    @location(2) uv: vec2<f32>,
+    @location(3) tangent: vec4<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) world_pos: vec3<f32>,
+    @location(1) world_normal: vec3<f32>,
+    @location(2) uv: vec2<f32>,
+    @location(3) world_tangent: vec4<f32>,
+};
+
+@vertex
+fn vs_main(input: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+    let world_pos = uniforms.model * vec4<f32>(input.position, 1.0);
+    out.world_pos = world_pos.xyz;
+    out.clip_position = uniforms.view_proj * world_pos;
+    // La escala del modelo es uniforme, así que basta la matriz model
+    out.world_normal = normalize((uniforms.model * vec4<f32>(input.normal, 0.0)).xyz);
+    out.world_tangent = vec4<f32>(normalize((uniforms.model * vec4<f32>(input.tangent.xyz, 0.0)).xyz), input.tangent.w);
+    out.uv = input.uv;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let surface = sample_material(in.uv, in.world_normal, in.world_tangent);
+
+    // Sin culling se ven las dos caras: iluminar la que mira a la cámara
+    var normal = surface.normal;
+    if (dot(normal, light.camera_position - in.world_pos) < 0.0) {
+        normal = -normal;
+    }
+
+    let lit = sun_light(in.world_pos, normal, 32.0);
+    var color = shade(surface.albedo.rgb, lit, 0.5 * surface.specular);
+    color = color + surface.emissive;
+
+    return vec4<f32>(color, 1.0);
+}
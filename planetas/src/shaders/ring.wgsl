@@ -9,12 +9,15 @@ var<uniform> uniforms: Uniforms;
 
 struct VertexInput {
     @location(0) position: vec3<f32>,
+    @location(1) normal: vec3<f32>,
+    @location(2) uv: vec2<f32>,
 };
 
 struct VertexOutput {
     @builtin(position) clip_position: vec4<f32>,
     @location(0) world_pos: vec3<f32>,
     @location(1) uv: vec2<f32>,
+    @location(2) world_normal: vec3<f32>,
 };
 
 @vertex
@@ -23,10 +26,11 @@ fn vs_main(input: VertexInput) -> VertexOutput {
     let world_pos = uniforms.model * vec4<f32>(input.position, 1.0);
     out.world_pos = world_pos.xyz;
     out.clip_position = uniforms.view_proj * world_pos;
+    out.world_normal = normalize((uniforms.model * vec4<f32>(input.normal, 0.0)).xyz);
     
-    // Calcular UV basado en la distancia del centro
-    let dist = length(input.position.xz);
-    out.uv = vec2<f32>(dist, atan2(input.position.z, input.position.x));
+    // UV: radio en la malla (de 1 en el borde interior a 2 en el exterior) y
+    // ángulo alrededor del planeta
+    out.uv = vec2<f32>(1.0 + input.uv.y, input.uv.x * 6.2831853);
     
     return out;
 }
@@ -46,6 +50,18 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     var color = mix(color1, color2, ring_pattern);
     color = mix(color, color3, spiral * 0.5);
     
+    // Iluminación del sol por ambas caras del anillo
+    var normal = normalize(in.world_normal);
+    if (dot(normal, light.camera_position - in.world_pos) < 0.0) {
+        normal = -normal;
+    }
+    let lit = sun_light(in.world_pos, normal, 16.0);
+    let albedo = color;
+    color = shade(color, lit, 0.2);
+    
+    // Las bandas más brillantes emiten luz neón
+    color = color + albedo * smoothstep(0.75, 1.0, ring_pattern) * 1.5;
+    
     // Transparencia variable
     let alpha = ring_pattern * 0.6 + 0.3;
     
@@ -0,0 +1,42 @@
+// Pasada de sombras: escribe la distancia lineal al sol (normalizada por `far`)
+// en una cara del mapa de sombras omnidireccional.
+struct Uniforms {
+    view_proj: mat4x4<f32>,
+    model: mat4x4<f32>,
+    time: f32,
+};
+
+struct ShadowFace {
+    view_proj: mat4x4<f32>,
+    light_position: vec3<f32>,
+    far: f32,
+};
+
+@group(0) @binding(0)
+var<uniform> uniforms: Uniforms;
+
+@group(1) @binding(0)
+var<uniform> face: ShadowFace;
+
+struct VertexInput {
+    @location(0) position: vec3<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) world_pos: vec3<f32>,
+};
+
+@vertex
+fn vs_main(input: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+    let world_pos = uniforms.model * vec4<f32>(input.position, 1.0);
+    out.world_pos = world_pos.xyz;
+    out.clip_position = face.view_proj * world_pos;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @builtin(frag_depth) f32 {
+    return length(in.world_pos - face.light_position) / face.far;
+}
@@ -9,12 +9,15 @@ var<uniform> uniforms: Uniforms;
 
 struct VertexInput {
     @location(0) position: vec3<f32>,
+    @location(1) normal: vec3<f32>,
+    @location(2) uv: vec2<f32>,
 };
 
 struct VertexOutput {
     @builtin(position) clip_position: vec4<f32>,
     @location(0) world_pos: vec3<f32>,
     @location(1) normal: vec3<f32>,
+    @location(2) world_normal: vec3<f32>,
 };
 
 @vertex
@@ -23,14 +26,13 @@ fn vs_main(input: VertexInput) -> VertexOutput {
     let world_pos = uniforms.model * vec4<f32>(input.position, 1.0);
     out.world_pos = world_pos.xyz;
     out.clip_position = uniforms.view_proj * world_pos;
-    out.normal = normalize(input.position);
+    out.normal = input.normal;
+    out.world_normal = normalize((uniforms.model * vec4<f32>(out.normal, 0.0)).xyz);
     return out;
 }
 
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
-    let normal = normalize(in.normal);
-    
     // Color base metálico plateado
     let metal_color = vec3<f32>(0.8, 0.85, 0.9);
     
@@ -38,16 +40,9 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     let neon_glow = sin(uniforms.time * 5.0) * 0.5 + 0.5;
     let accent_color = vec3<f32>(0.0, 0.8, 1.0) * neon_glow;
     
-    // Iluminación
-    let light_dir = normalize(vec3<f32>(1.0, 1.0, 1.0));
-    let diff = max(dot(normal, light_dir), 0.0);
-    var color = metal_color * (0.3 + diff * 0.7);
-    
-    // Especular metálico
-    let view_dir = normalize(vec3<f32>(0.0, 0.0, 1.0));
-    let reflect_dir = reflect(-light_dir, normal);
-    let spec = pow(max(dot(view_dir, reflect_dir), 0.0), 32.0);
-    color = color + vec3<f32>(1.0, 1.0, 1.0) * spec * 0.5;
+    // Iluminación del sol con especular metálico
+    let lit = sun_light(in.world_pos, normalize(in.world_normal), 32.0);
+    var color = shade(metal_color, lit, 0.5);
     
     // Añadir acentos de neón
     color = color + accent_color * 0.2;
@@ -0,0 +1,51 @@
+// Funciones compartidas del cielo. Este archivo se antepone a skybox.wgsl y a
+// sky_bake.wgsl (que hornea el mismo cielo en un cubemap).
+
+fn hash(p: vec3<f32>) -> f32 {
+    var p3 = fract(p * 0.1031);
+    p3 = p3 + dot(p3, p3.yzx + 33.33);
+    return fract((p3.x + p3.y) * p3.z);
+}
+
+// Estrellas con parpadeo y nebulosa de colores en la dirección `dir`
+fn starfield(dir: vec3<f32>, time: f32) -> vec3<f32> {
+    // Color base del espacio
+    let space_color = vec3<f32>(0.01, 0.01, 0.05);
+    var color = space_color;
+    
+    // Generar estrellas usando hash
+    let star_density = 400.0;
+    let star_pos = floor(dir * star_density);
+    let star_hash = hash(star_pos);
+    
+    // Crear estrellas
+    if (star_hash > 0.996) {
+        let star_brightness = star_hash * 2.0;
+        let star_color = mix(
+            vec3<f32>(1.0, 1.0, 1.0),
+            vec3<f32>(0.8, 0.9, 1.0),
+            star_hash
+        );
+        color = color + star_color * star_brightness;
+    }
+    
+    // Estrellas parpadeantes
+    let twinkle = sin(star_hash * 100.0 + time * 3.0) * 0.5 + 0.5;
+    if (star_hash > 0.996) {
+        color = color * (0.7 + twinkle * 0.3);
+    }
+    
+    // Nebulosa de colores sutiles
+    let nebula = sin(dir.x * 2.0 + time * 0.1) * 
+                 sin(dir.y * 2.0 - time * 0.15) * 
+                 sin(dir.z * 2.0 + time * 0.12);
+    let nebula_color = vec3<f32>(0.3, 0.1, 0.4) * max(nebula, 0.0) * 0.15;
+    return color + nebula_color;
+}
+
+// Coordenada de cubemap de una dirección del mundo. Las caras siguen la
+// convención habitual (pensada para un sistema zurdo); como el mundo es diestro
+// se espeja X para que las imágenes no salgan invertidas. Es su propia inversa.
+fn sky_direction(dir: vec3<f32>) -> vec3<f32> {
+    return vec3<f32>(-dir.x, dir.y, dir.z);
+}
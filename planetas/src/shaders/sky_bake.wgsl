@@ -0,0 +1,63 @@
+// Generación de los cubemaps del cielo al arrancar: cada pasada dibuja una cara
+// con un triángulo de pantalla completa. Se antepone sky.wgsl.
+struct BakeParams {
+    face: u32,
+};
+
+@group(0) @binding(0)
+var<uniform> params: BakeParams;
+
+@group(0) @binding(1)
+var panorama: texture_2d<f32>;
+
+@group(0) @binding(2)
+var panorama_sampler: sampler;
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+};
+
+@vertex
+fn vs_fullscreen(@builtin(vertex_index) index: u32) -> VertexOutput {
+    let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0, 0.0, 1.0);
+    out.uv = uv;
+    return out;
+}
+
+// Dirección del mundo que ve el texel `uv` de la cara actual. Las caras van en el
+// orden de capas de wgpu (+X, -X, +Y, -Y, +Z, -Z) con V hacia abajo.
+fn face_direction(uv: vec2<f32>) -> vec3<f32> {
+    let s = uv.x * 2.0 - 1.0;
+    let t = uv.y * 2.0 - 1.0;
+    var cube: vec3<f32>;
+    switch params.face {
+        case 0u: { cube = vec3<f32>(1.0, -t, -s); }
+        case 1u: { cube = vec3<f32>(-1.0, -t, s); }
+        case 2u: { cube = vec3<f32>(s, 1.0, t); }
+        case 3u: { cube = vec3<f32>(s, -1.0, -t); }
+        case 4u: { cube = vec3<f32>(s, -t, 1.0); }
+        default: { cube = vec3<f32>(-s, -t, -1.0); }
+    }
+    return normalize(sky_direction(cube));
+}
+
+// Cielo procedural congelado en t = 0 (sin parpadeo ni nebulosa animada)
+@fragment
+fn fs_starfield(in: VertexOutput) -> @location(0) vec4<f32> {
+    return vec4<f32>(starfield(face_direction(in.uv), 0.0), 1.0);
+}
+
+// Panorama equirectangular: U da la vuelta alrededor de Y (el centro mira hacia
+// -Z) y V va de arriba abajo
+@fragment
+fn fs_panorama(in: VertexOutput) -> @location(0) vec4<f32> {
+    let dir = face_direction(in.uv);
+    let uv = vec2<f32>(
+        0.5 + atan2(dir.x, -dir.z) / 6.2831853,
+        acos(clamp(dir.y, -1.0, 1.0)) / 3.1415927,
+    );
+    return vec4<f32>(textureSampleLevel(panorama, panorama_sampler, uv, 0.0).rgb, 1.0);
+}
@@ -7,6 +7,20 @@ struct Uniforms {
 @group(0) @binding(0)
 var<uniform> uniforms: Uniforms;
 
+// Cielo en cubemap (solo `fs_cubemap`)
+struct SkyParams {
+    intensity: f32,
+};
+
+@group(1) @binding(0)
+var<uniform> sky: SkyParams;
+
+@group(1) @binding(1)
+var sky_cubemap: texture_cube<f32>;
+
+@group(1) @binding(2)
+var sky_sampler: sampler;
+
 struct VertexInput {
     @location(0) position: vec3<f32>,
 };
@@ -14,6 +28,8 @@ struct VertexInput {
 struct VertexOutput {
     @builtin(position) clip_position: vec4<f32>,
     @location(0) world_pos: vec3<f32>,
+    // Dirección desde la cámara (el cubo está centrado en ella)
+    @location(1) direction: vec3<f32>,
 };
 
 @vertex
@@ -21,52 +37,22 @@ fn vs_main(input: VertexInput) -> VertexOutput {
     var out: VertexOutput;
     let world_pos = uniforms.model * vec4<f32>(input.position, 1.0);
     out.world_pos = world_pos.xyz;
-    out.clip_position = uniforms.view_proj * world_pos;
+    out.direction = input.position;
+    // Fijar la profundidad en el plano lejano: las esquinas del cubo quedan más
+    // lejos que zfar y se recortarían
+    let clip = uniforms.view_proj * world_pos;
+    out.clip_position = clip.xyww;
     return out;
 }
 
-fn hash(p: vec3<f32>) -> f32 {
-    var p3 = fract(p * 0.1031);
-    p3 = p3 + dot(p3, p3.yzx + 33.33);
-    return fract((p3.x + p3.y) * p3.z);
-}
-
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     let dir = normalize(in.world_pos);
-    
-    // Color base del espacio
-    let space_color = vec3<f32>(0.01, 0.01, 0.05);
-    var color = space_color;
-    
-    // Generar estrellas usando hash
-    let star_density = 400.0;
-    let star_pos = floor(dir * star_density);
-    let star_hash = hash(star_pos);
-    
-    // Crear estrellas
-    if (star_hash > 0.996) {
-        let star_brightness = star_hash * 2.0;
-        let star_color = mix(
-            vec3<f32>(1.0, 1.0, 1.0),
-            vec3<f32>(0.8, 0.9, 1.0),
-            star_hash
-        );
-        color = color + star_color * star_brightness;
-    }
-    
-    // Estrellas parpadeantes
-    let twinkle = sin(star_hash * 100.0 + uniforms.time * 3.0) * 0.5 + 0.5;
-    if (star_hash > 0.996) {
-        color = color * (0.7 + twinkle * 0.3);
-    }
-    
-    // Nebulosa de colores sutiles
-    let nebula = sin(dir.x * 2.0 + uniforms.time * 0.1) * 
-                 sin(dir.y * 2.0 - uniforms.time * 0.15) * 
-                 sin(dir.z * 2.0 + uniforms.time * 0.12);
-    let nebula_color = vec3<f32>(0.3, 0.1, 0.4) * max(nebula, 0.0) * 0.15;
-    color = color + nebula_color;
-    
-    return vec4<f32>(color, 1.0);
-}
\ No newline at end of file
+    return vec4<f32>(starfield(dir, uniforms.time), 1.0);
+}
+
+@fragment
+fn fs_cubemap(in: VertexOutput) -> @location(0) vec4<f32> {
+    let color = textureSample(sky_cubemap, sky_sampler, sky_direction(in.direction)).rgb;
+    return vec4<f32>(color * sky.intensity, 1.0);
+}